@@ -4,7 +4,13 @@ use protoc_rust::Customize;
 fn main() {
     protoc_rust::Codegen::new()
         .out_dir("src/proto/chess")
-        .inputs(&["proto/chess/cell.proto", "proto/chess/move_command.proto"])
+        .inputs(&[
+            "proto/chess/bughouse_update.proto",
+            "proto/chess/cell.proto",
+            "proto/chess/game_setup.proto",
+            "proto/chess/move_command.proto",
+            "proto/chess/referee_message.proto",
+        ])
         .include("proto")
         .customize(Customize {
             expose_fields: Some(true),
@@ -1,73 +1,525 @@
 use crate::common;
 use crate::common::Color;
 
-use crate::common::StepResultData;
+use crate::common::{CastlingSide, GameOutcome, StepResultData};
+use crate::moves::Move;
 use crate::pieces::Piece;
 use crate::pieces::PieceType;
 use crate::positions::ChessError::*;
+use crate::positions::{Vertical, MAX_SIZE};
 use crate::ChessResult;
-use num::FromPrimitive;
 
 use crate::positions::Position;
+use crate::variants::standard::Standard;
+use crate::variants::Variant;
 
 use std::cell::RefCell;
 use std::mem;
 use std::rc::Rc;
 
+const POCKET_ORDER: [PieceType; 10] = [
+    PieceType::Amazon,
+    PieceType::Queen,
+    PieceType::Chancellor,
+    PieceType::Archbishop,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Camel,
+    PieceType::Grasshopper,
+    PieceType::Pawn,
+];
+
+// Duck emoji is double width in most terminals and would shift the rest of the row
+const DUCK_ICON: char = '\u{25C6}';
+
+#[derive(Clone)]
 pub struct Board {
-    contents: [[Option<Piece>; 8]; 8],
+    // Only width x height corner is used, the rest of cells stay empty
+    contents: [[Option<Piece>; MAX_SIZE as usize]; MAX_SIZE as usize],
+    width: u8,
+    height: u8,
+    active_color: Color,
+    en_passant: Option<Position>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    // Checks given by white and black, counted only by variants which care about them
+    checks_given: [u32; 2],
+    // Captured pieces available for drops, kept sorted from the strongest piece to pawn
+    pockets: [Vec<PieceType>; 2],
+    // Neutral blocker of Duck chess, it isn't a piece of any side
+    duck: Option<Position>,
+    variant: Rc<dyn Variant>,
 }
 
 impl Board {
     pub fn new() -> Rc<RefCell<Board>> {
-        let result = Rc::new(RefCell::new(Board {
-            contents: Default::default(),
-        }));
-        Board::fill_board(&result);
-        result
+        Board::new_variant(Rc::new(Standard::new())).unwrap()
+    }
+
+    pub fn new_variant(variant: Rc<dyn Variant>) -> ChessResult<Rc<RefCell<Board>>> {
+        let fen = variant.starting_fen();
+        Board::from_variant_fen(&fen, variant)
+    }
+
+    // Castling rights can be given both in X-FEN (KQkq) and Shredder-FEN (HAha) forms
+    pub fn from_fen(fen: &str) -> ChessResult<Rc<RefCell<Board>>> {
+        Board::from_variant_fen(fen, Rc::new(Standard::new()))
+    }
+
+    pub fn from_variant_fen(
+        fen: &str,
+        variant: Rc<dyn Variant>,
+    ) -> ChessResult<Rc<RefCell<Board>>> {
+        Ok(Rc::new(RefCell::new(Board::parse_fen(fen, variant)?)))
+    }
+
+    pub fn variant(&self) -> &Rc<dyn Variant> {
+        &self.variant
+    }
+
+    // X-FEN, which is the same as regular FEN for standard chess positions
+    pub fn to_fen(&self) -> String {
+        let mut rows = Vec::new();
+        let used_rows = &self.contents[..self.height as usize];
+        for (vert, row) in used_rows.iter().enumerate().rev() {
+            let mut fen_row = String::new();
+            let mut empty = 0;
+            for (hor, cell) in row[..self.width as usize].iter().enumerate() {
+                let is_duck = self
+                    .duck
+                    .map(|duck| duck.hor as usize == hor && duck.vert as usize == vert)
+                    .unwrap_or(false);
+                if is_duck {
+                    if empty > 0 {
+                        fen_row.push_str(&empty.to_string());
+                        empty = 0;
+                    }
+                    fen_row.push('*');
+                    continue;
+                }
+                match cell {
+                    None => empty += 1,
+                    Some(piece) => {
+                        if empty > 0 {
+                            fen_row.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        fen_row.push(match piece.color {
+                            Color::White => piece.piece_type.letter(),
+                            Color::Black => piece.piece_type.letter().to_ascii_lowercase(),
+                        });
+                        if piece.is_promoted() && self.variant.has_pockets() {
+                            fen_row.push('~');
+                        }
+                    }
+                }
+            }
+            if empty > 0 {
+                fen_row.push_str(&empty.to_string());
+            }
+            rows.push(fen_row);
+        }
+        let active_color = match self.active_color {
+            Color::White => "w",
+            Color::Black => "b",
+        };
+        let castling: String = [Color::White, Color::Black]
+            .iter()
+            .flat_map(|color| self.castling_rights_fen(*color))
+            .collect();
+        let en_passant = self
+            .en_passant
+            .map(|square| square.to_string().to_ascii_lowercase())
+            .unwrap_or_else(|| "-".to_string());
+        let mut placement = rows.join("/");
+        if self.variant.has_pockets() {
+            let pockets: String = self
+                .pocket(Color::White)
+                .iter()
+                .map(|piece_type| piece_type.letter())
+                .chain(
+                    self.pocket(Color::Black)
+                        .iter()
+                        .map(|piece_type| piece_type.letter().to_ascii_lowercase()),
+                )
+                .collect();
+            placement = format!("{}[{}]", placement, pockets);
+        }
+        let mut fen = format!(
+            "{} {} {} {} {} {}",
+            placement,
+            active_color,
+            if castling.is_empty() { "-" } else { &castling },
+            en_passant,
+            self.halfmove_clock,
+            self.fullmove_number
+        );
+        if let Some(state) = self.variant.fen_state(self) {
+            fen.push(' ');
+            fen.push_str(&state);
+        }
+        fen
+    }
+
+    fn parse_fen(fen: &str, variant: Rc<dyn Variant>) -> ChessResult<Board> {
+        let invalid = || InvalidInput(fen.to_string());
+        let mut fields = fen.split_whitespace();
+        let (placement, pockets) = match fields
+            .next()
+            .ok_or_else(invalid)?
+            .split('[')
+            .collect::<Vec<&str>>()[..]
+        {
+            [placement] => (placement, None),
+            [placement, pockets] => (
+                placement,
+                Some(pockets.strip_suffix(']').ok_or_else(invalid)?),
+            ),
+            _ => return Err(invalid()),
+        };
+        let active_color = match fields.next() {
+            Some("w") => Color::White,
+            Some("b") => Color::Black,
+            _ => return Err(invalid()),
+        };
+        let castling = fields.next().unwrap_or("-");
+        let en_passant = match fields.next().unwrap_or("-") {
+            "-" => None,
+            square => Some(square.parse::<Position>()?),
+        };
+        let halfmove_clock = fields
+            .next()
+            .unwrap_or("0")
+            .parse()
+            .map_err(|_| invalid())?;
+        let fullmove_number = fields
+            .next()
+            .unwrap_or("1")
+            .parse()
+            .map_err(|_| invalid())?;
+
+        let mut board = Board::empty(variant);
+        board.active_color = active_color;
+        board.en_passant = en_passant;
+        board.halfmove_clock = halfmove_clock;
+        board.fullmove_number = fullmove_number;
+
+        // Empty squares count may have several digits on wide boards
+        let (width, height) = (board.width as i8, board.height as i8);
+        let square = |hor: i8, vert: i8| Position::at(hor, vert).filter(|_| hor < width);
+        let rows: Vec<&str> = placement.split('/').collect();
+        if rows.len() != height as usize {
+            return Err(invalid());
+        }
+        for (row, vert) in rows.iter().zip((0..height).rev()) {
+            let mut hor = 0;
+            // Wider than positions, so overlong digit runs are caught instead of overflowing
+            let mut skip: i32 = 0;
+            for letter in row.chars() {
+                if let Some(digit) = letter.to_digit(10) {
+                    skip = skip
+                        .checked_mul(10)
+                        .and_then(|skip| skip.checked_add(digit as i32))
+                        .filter(|skip| i32::from(hor) + skip <= i32::from(width))
+                        .ok_or_else(invalid)?;
+                    continue;
+                }
+                hor += skip as i8;
+                skip = 0;
+                if letter == '~' {
+                    let promoted = Position::at(hor - 1, vert).ok_or_else(invalid)?;
+                    match board.contents[promoted.vert as usize][promoted.hor as usize].as_mut() {
+                        Some(piece) => piece.mark_as_promoted(),
+                        None => return Err(invalid()),
+                    }
+                    continue;
+                }
+                if letter == '*' && board.variant.has_duck() {
+                    board.duck = Some(square(hor, vert).ok_or_else(invalid)?);
+                    hor += 1;
+                    continue;
+                }
+                let piece_type = PieceType::from_letter(letter).ok_or_else(invalid)?;
+                let color = if letter.is_ascii_uppercase() {
+                    Color::White
+                } else {
+                    Color::Black
+                };
+                let position = square(hor, vert).ok_or_else(invalid)?;
+                board.put_piece(Piece::new(piece_type, color, position));
+                hor += 1;
+            }
+            if i32::from(hor) + skip != i32::from(width) {
+                return Err(invalid());
+            }
+        }
+
+        let mut unmoved = Vec::new();
+        for letter in castling.chars().filter(|letter| *letter != '-') {
+            let color = if letter.is_ascii_uppercase() {
+                Color::White
+            } else {
+                Color::Black
+            };
+            let king = board
+                .king_position(color)
+                .filter(|king| king.vert == board.first_vertical(color))
+                .ok_or_else(invalid)?;
+            let rooks = board.castling_rooks(color);
+            let rook = match letter.to_ascii_lowercase() {
+                'k' => rooks.iter().rev().find(|rook| rook.hor > king.hor),
+                'q' => rooks.iter().find(|rook| rook.hor < king.hor),
+                file @ 'a'..='z' => rooks
+                    .iter()
+                    .find(|rook| rook.hor as u8 == file as u8 - b'a'),
+                _ => None,
+            };
+            unmoved.push(king);
+            unmoved.push(*rook.ok_or_else(invalid)?);
+        }
+        let variant = board.variant.clone();
+        for row in board.contents.iter_mut() {
+            for piece in row.iter_mut().flatten() {
+                let is_pawn_at_start = piece.piece_type == PieceType::Pawn
+                    && variant
+                        .double_step_ranks(piece.color)
+                        .contains(&piece.position.vert);
+                if !is_pawn_at_start && !unmoved.contains(&piece.position) {
+                    piece.mark_as_moved();
+                }
+            }
+        }
+        if let Some(pockets) = pockets {
+            if !board.variant.has_pockets() {
+                return Err(invalid());
+            }
+            for letter in pockets.chars() {
+                let piece_type = PieceType::from_letter(letter).ok_or_else(invalid)?;
+                let color = if letter.is_ascii_uppercase() {
+                    Color::White
+                } else {
+                    Color::Black
+                };
+                board.add_to_pocket(color, piece_type);
+            }
+        }
+        if let Some(state) = fields.next() {
+            let variant = board.variant.clone();
+            variant.parse_fen_state(&mut board, state)?;
+        }
+        Ok(board)
     }
 
     pub fn cell(&self, position: &Position) -> &Option<Piece> {
         &self.contents[position.vert as usize][position.hor as usize]
     }
 
-    pub fn move_piece(
-        &mut self,
-        from: &Position,
-        to: &Position,
-        color: Color,
-    ) -> ChessResult<StepResultData> {
-        {
-            let ref piece = self.cell(from).as_ref().ok_or_else(|| EmptyCell(*from))?;
+    pub fn active_color(&self) -> Color {
+        self.active_color
+    }
+
+    pub fn set_active_color(&mut self, color: Color) {
+        self.active_color = color;
+    }
+
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    pub fn checks_given(&self, color: Color) -> u32 {
+        self.checks_given[color as usize]
+    }
+
+    pub fn set_checks_given(&mut self, color: Color, checks: u32) {
+        self.checks_given[color as usize] = checks;
+    }
+
+    pub fn pocket(&self, color: Color) -> &[PieceType] {
+        &self.pockets[color as usize]
+    }
+
+    pub fn add_to_pocket(&mut self, color: Color, piece_type: PieceType) {
+        let pocket = &mut self.pockets[color as usize];
+        pocket.push(piece_type);
+        pocket.sort_by_key(|pocketed| POCKET_ORDER.iter().position(|order| order == pocketed));
+    }
+
+    pub fn duck(&self) -> Option<Position> {
+        self.duck
+    }
+
+    // Neither a piece nor the duck stands there
+    pub fn is_empty(&self, position: &Position) -> bool {
+        self.cell(position).is_none() && self.duck != Some(*position)
+    }
+
+    pub fn en_passant(&self) -> Option<Position> {
+        self.en_passant
+    }
+
+    pub fn move_piece(&mut self, step: &Move, color: Color) -> ChessResult<StepResultData> {
+        if step.drop.is_none() {
+            let piece = self.cell(&step.from).as_ref().ok_or(EmptyCell(step.from))?;
             if piece.color != color {
-                return Err(WrongColor(*from));
-            }
-            if !piece.movement.can_move(piece, to, self) {
-                return Err(InvalidMove(*from, *to));
+                return Err(WrongColor(step.from));
             }
         }
-        let mut raw_result = match self.cell(to) {
-            Some(piece) if color == piece.color => return Err(InvalidMove(*from, *to)),
-            Some(_) => StepResultData::Eaten(self.extract_piece(to).unwrap()),
-            None => StepResultData::Moved,
+        if self.variant.has_duck() && step.duck.is_none() {
+            return Err(DuckRequired(step.from, step.to));
+        }
+        let (next, mut result) = self
+            .try_move(step, color)
+            .ok_or(InvalidMove(step.from, step.to))?;
+        self.variant.check_move(self, &result)?;
+        *self = next;
+        result.outcome = self.game_outcome();
+        Ok(result)
+    }
+
+    pub fn game_outcome(&self) -> Option<GameOutcome> {
+        self.game_outcome_with(self.has_legal_moves(self.active_color))
+    }
+
+    // Same as game_outcome() for callers who already know if the side to move has any move
+    pub fn game_outcome_with(&self, has_legal_moves: bool) -> Option<GameOutcome> {
+        self.variant.outcome(self, has_legal_moves)
+    }
+
+    // Rook to castle with and castling side, if king's step is a castling
+    pub fn castling_rook(&self, king: &Piece, to: &Position) -> Option<(Position, CastlingSide)> {
+        if king.piece_type != PieceType::King || king.is_moved() || to.vert != king.position.vert {
+            return None;
+        }
+        let rook_position = if self.variant.is_chess960() {
+            *to
+        } else {
+            let (king_side_to, _) = self.castling_destinations(king.color, CastlingSide::King);
+            let (queen_side_to, _) = self.castling_destinations(king.color, CastlingSide::Queen);
+            let (hor_diff, _) = to - &king.position;
+            let hor = match hor_diff {
+                2..=i8::MAX if *to == king_side_to => self.width as i8 - 1,
+                i8::MIN..=-2 if *to == queen_side_to => 0,
+                _ => return None,
+            };
+            Position::at(hor, to.vert as i8)?
         };
-        let mut piece = self.extract_piece(from).unwrap();
-        piece.mark_as_moved();
-        piece.position = *to;
-        if piece.movement.is_enemy_in_check(&piece, self) {
-            raw_result = StepResultData::Check(piece.color);
+        let is_castling_rook = self
+            .cell(&rook_position)
+            .as_ref()
+            .map(|rook| {
+                rook.piece_type == PieceType::Rook && rook.color == king.color && !rook.is_moved()
+            })
+            .unwrap_or(false);
+        if !is_castling_rook {
+            return None;
         }
-        self.contents[to.vert as usize][to.hor as usize] = Some(piece);
-        Ok(raw_result)
+        let side = if rook_position.hor > king.position.hor {
+            CastlingSide::King
+        } else {
+            CastlingSide::Queen
+        };
+        Some((rook_position, side))
     }
 
-    pub fn draw(&self) {
+    // Final king and rook positions, same for standard chess and Chess960.
+    // King goes to the second file from the edge and rook stands next to it on wider boards too
+    pub fn castling_destinations(&self, color: Color, side: CastlingSide) -> (Position, Position) {
+        let (king_hor, rook_hor) = match side {
+            CastlingSide::King => (self.width as i8 - 2, self.width as i8 - 3),
+            CastlingSide::Queen => (2, 3),
+        };
+        let vert = self.first_vertical(color) as i8;
+        (
+            Position::at(king_hor, vert).unwrap(),
+            Position::at(rook_hor, vert).unwrap(),
+        )
+    }
+
+    pub fn castling_move(&self, color: Color, side: CastlingSide) -> Option<Move> {
+        self.legal_moves(color).into_iter().find(|step| {
+            self.cell(&step.from)
+                .as_ref()
+                .and_then(|king| self.castling_rook(king, &step.to))
+                .map(|(_, castling_side)| castling_side == side)
+                .unwrap_or(false)
+        })
+    }
+
+    pub fn legal_moves(&self, color: Color) -> Vec<Move> {
+        self.legal_moves_where(color, |_| true)
+    }
+
+    // Captures and promotions only, without checking the rest of the moves for legality
+    pub fn legal_captures(&self, color: Color) -> Vec<Move> {
+        self.legal_moves_where(color, |step| {
+            step.promotion.is_some() || self.is_capture(step)
+        })
+    }
+
+    // Stops at the first legal move found, variant filters never leave nothing out of something
+    pub fn has_legal_moves(&self, color: Color) -> bool {
+        self.pseudo_legal_moves(color)
+            .iter()
+            .any(|step| !self.legal_variations(step, color).is_empty())
+    }
+
+    // Applies move without any validation, meant to be used with moves from legal_moves()
+    pub fn after_move(&self, step: &Move) -> Board {
+        let mut next = self.clone();
+        next.apply_move(step);
+        next
+    }
+
+    // Squares out of visible ones are drawn as fog, everything is visible if it is None
+    pub fn draw(&self, highlighted: &[Position], visible: Option<&[Position]>) {
+        self.draw_at(3, highlighted, visible);
+    }
+
+    // Same as draw, but starting from the given terminal column, like the second Bughouse board
+    pub fn draw_at(&self, column: u16, highlighted: &[Position], visible: Option<&[Position]>) {
         print!("{}", termion::color::Fg(termion::color::Black));
-        let mut color = common::Color::White;
-        for i in (0u16..8).rev() {
-            print!("{}", termion::cursor::Goto(3, 10 - i));
-            for j in 0u16..8 {
-                if color == common::Color::Black {
+        let marked = self.variant.marked_squares();
+        let notes = self.variant.notes(self);
+        let (width, height) = (u16::from(self.width), u16::from(self.height));
+        for i in (0..height).rev() {
+            print!("{}", termion::cursor::Goto(column, height + 2 - i));
+            // A1 is always a dark square
+            let mut color = if i % 2 == 1 {
+                common::Color::White
+            } else {
+                common::Color::Black
+            };
+            for j in 0..width {
+                let position = Position::at(j as i8, i as i8);
+                let is_highlighted = position
+                    .map(|position| highlighted.contains(&position))
+                    .unwrap_or(false);
+                let is_marked = position
+                    .map(|position| marked.contains(&position))
+                    .unwrap_or(false);
+                let is_fogged = match (position, visible) {
+                    (Some(position), Some(visible)) => !visible.contains(&position),
+                    _ => false,
+                };
+                if is_fogged {
+                    print!("{} ", termion::color::Bg(termion::color::LightBlack));
+                    color = !color;
+                } else if is_highlighted {
+                    print!(
+                        "{}{}",
+                        termion::color::Bg(termion::color::LightRed),
+                        self.piece_icon(i.into(), j.into())
+                    );
+                    color = !color;
+                } else if is_marked {
+                    print!(
+                        "{}{}",
+                        termion::color::Bg(termion::color::LightGreen),
+                        self.piece_icon(i.into(), j.into())
+                    );
+                    color = !color;
+                } else if color == common::Color::Black {
                     print!(
                         "{}{}",
                         termion::color::Bg(termion::color::Yellow),
@@ -83,35 +535,453 @@ impl Board {
                     color = common::Color::Black;
                 }
             }
-            color = if color == common::Color::Black {
-                common::Color::White
-            } else {
-                common::Color::Black
-            }
         }
         println!(
             "{}{}",
             termion::color::Bg(termion::color::Reset),
             termion::color::Fg(termion::color::Reset)
         );
+        if !notes.is_empty() {
+            for (line, note) in (3u16..).zip(notes.iter()) {
+                print!(
+                    "{}{}",
+                    termion::cursor::Goto(column + width + 3, line),
+                    note
+                );
+            }
+            print!("{}", termion::cursor::Goto(1, height + 3));
+        }
     }
 
     pub fn is_in_check_state(&self) -> Option<Color> {
-        for i in 0..8 {
-            for j in 0..8 {
-                let now = Position::at(i, j)
-                    .and_then(|pos| self.cell(&pos).as_ref())
-                    .filter(|piece| piece.movement.is_enemy_in_check(piece, self))
-                    .map(|piece| !piece.color);
-                if now.is_some() {
-                    return now;
+        [Color::White, Color::Black]
+            .iter()
+            .copied()
+            .find(|color| self.is_in_check(*color))
+    }
+
+    pub fn is_in_check(&self, color: Color) -> bool {
+        !self.checkers(color).is_empty()
+    }
+
+    // Enemy pieces giving check to the king of color, two of them mean double check
+    pub fn checkers(&self, color: Color) -> Vec<&Piece> {
+        self.king_position(color)
+            .map(|king| self.attackers_of(&king, !color))
+            .unwrap_or_default()
+    }
+
+    // Pieces of color hitting the square, no matter if it is empty, enemy's or their own
+    pub fn attackers_of(&self, square: &Position, color: Color) -> Vec<&Piece> {
+        self.pieces()
+            .filter(|piece| piece.color == color)
+            .filter(|piece| piece.movement.attacks(piece, square, self))
+            .collect()
+    }
+
+    // Copy of the board with only pieces on visible squares, safe to show to a single player
+    pub fn masked(&self, visible: &[Position]) -> Board {
+        let mut result = self.clone();
+        for row in result.contents.iter_mut() {
+            for cell in row.iter_mut() {
+                let is_hidden = cell
+                    .as_ref()
+                    .map(|piece| !visible.contains(&piece.position))
+                    .unwrap_or(false);
+                if is_hidden {
+                    *cell = None;
+                }
+            }
+        }
+        // Both would tell whether the last hidden move was a pawn move or a capture
+        result.en_passant = None;
+        result.halfmove_clock = 0;
+        result
+    }
+
+    pub fn attacked_squares(&self, color: Color) -> Vec<Position> {
+        self.squares()
+            .filter(|square| !self.attackers_of(square, color).is_empty())
+            .collect()
+    }
+
+    fn empty(variant: Rc<dyn Variant>) -> Board {
+        let (width, height) = variant.dimensions();
+        Board {
+            contents: Default::default(),
+            width,
+            height,
+            active_color: Color::White,
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            checks_given: [0; 2],
+            pockets: Default::default(),
+            duck: None,
+            variant,
+        }
+    }
+
+    // Unmoved rooks on the first row, ordered from A to H
+    fn castling_rooks(&self, color: Color) -> Vec<Position> {
+        let first_vert = self.first_vertical(color);
+        self.pieces()
+            .filter(|rook| rook.color == color && rook.piece_type == PieceType::Rook)
+            .filter(|rook| rook.position.vert == first_vert && !rook.is_moved())
+            .map(|rook| rook.position)
+            .collect()
+    }
+
+    // K and Q are used for the outermost rooks, file letters otherwise
+    pub fn castling_rights_fen(&self, color: Color) -> Vec<char> {
+        let first_vert = self.first_vertical(color);
+        let king = match self.king_position(color) {
+            Some(king) if king.vert == first_vert => king,
+            _ => return Vec::new(),
+        };
+        if self
+            .cell(&king)
+            .as_ref()
+            .map(|king| king.is_moved())
+            .unwrap_or(true)
+        {
+            return Vec::new();
+        }
+        let all_rooks: Vec<Position> = self
+            .pieces()
+            .filter(|rook| rook.color == color && rook.piece_type == PieceType::Rook)
+            .filter(|rook| rook.position.vert == first_vert)
+            .map(|rook| rook.position)
+            .collect();
+        let castling_rooks = self.castling_rooks(color);
+        let mut rights = Vec::new();
+        let king_side = |rook: &&Position| rook.hor > king.hor;
+        let queen_side = |rook: &&Position| rook.hor < king.hor;
+        let sides = [
+            (
+                castling_rooks.iter().rev().find(king_side),
+                all_rooks.iter().rev().find(king_side),
+                'K',
+            ),
+            (
+                castling_rooks.iter().find(queen_side),
+                all_rooks.iter().find(queen_side),
+                'Q',
+            ),
+        ];
+        for (rook, outermost, letter) in sides.iter() {
+            if let Some(rook) = rook {
+                rights.push(if *outermost == Some(*rook) {
+                    *letter
+                } else {
+                    (rook.hor as u8 + b'A') as char
+                });
+            }
+        }
+        match color {
+            Color::White => rights,
+            Color::Black => rights.iter().map(|c| c.to_ascii_lowercase()).collect(),
+        }
+    }
+
+    pub fn pieces(&self) -> impl Iterator<Item = &Piece> {
+        self.contents
+            .iter()
+            .flat_map(|row| row.iter())
+            .filter_map(|cell| cell.as_ref())
+    }
+
+    // Regular captures and en passant ones
+    pub fn is_capture(&self, step: &Move) -> bool {
+        let is_en_passant = step.drop.is_none()
+            && Some(step.to) == self.en_passant
+            && self
+                .cell(&step.from)
+                .as_ref()
+                .map(|piece| piece.piece_type == PieceType::Pawn)
+                .unwrap_or(false);
+        let is_enemy_there = match (self.cell(&step.from), self.cell(&step.to)) {
+            (Some(piece), Some(target)) => step.drop.is_none() && piece.color != target.color,
+            _ => false,
+        };
+        is_en_passant || is_enemy_there
+    }
+
+    // Pawns can't be dropped on the first and the last ranks
+    fn can_drop(&self, piece_type: PieceType, to: &Position, color: Color) -> bool {
+        let is_pawn_on_edge = piece_type == PieceType::Pawn
+            && (to.vert == self.first_vertical(color) || to.vert == self.last_vertical(color));
+        self.variant.has_pockets()
+            && self.pocket(color).contains(&piece_type)
+            && self.is_empty(to)
+            && !is_pawn_on_edge
+    }
+
+    // Dropped pawn on its start rank is allowed to make a double step
+    fn take_from_pocket(&mut self, piece_type: PieceType, to: &Position) -> Piece {
+        let color = self.active_color;
+        let pocket = &mut self.pockets[color as usize];
+        if let Some(index) = pocket.iter().position(|pocketed| *pocketed == piece_type) {
+            pocket.remove(index);
+        }
+        let mut piece = Piece::new(piece_type, color, *to);
+        let is_pawn_at_start = piece_type == PieceType::Pawn
+            && self.variant.double_step_ranks(color).contains(&to.vert);
+        if !is_pawn_at_start {
+            piece.mark_as_moved();
+        }
+        piece
+    }
+
+    fn legal_moves_where(&self, color: Color, is_wanted: impl Fn(&Move) -> bool) -> Vec<Move> {
+        let moves = self
+            .pseudo_legal_moves(color)
+            .iter()
+            .filter(|step| is_wanted(step))
+            .flat_map(|step| self.legal_variations(step, color))
+            .collect();
+        self.variant.filter_moves(self, moves)
+    }
+
+    // The move itself is checked once, in Duck chess it then comes with every possible duck square
+    fn legal_variations(&self, step: &Move, color: Color) -> Vec<Move> {
+        let next = match self.try_piece_move(step, color) {
+            Some((next, _)) => next,
+            None => return Vec::new(),
+        };
+        if !self.variant.has_duck() {
+            return vec![*step];
+        }
+        self.squares()
+            .filter(|duck| self.is_duck_placeable(&next, duck))
+            .map(|duck| Move {
+                duck: Some(duck),
+                ..*step
+            })
+            .collect()
+    }
+
+    fn pseudo_legal_moves(&self, color: Color) -> Vec<Move> {
+        let mut result = Vec::new();
+        for piece in self.pieces().filter(|piece| piece.color == color) {
+            let last_vert = self.last_vertical(color);
+            for to in self.squares() {
+                if !piece.movement.can_move(piece, &to, self) {
+                    continue;
                 }
+                if piece.piece_type == PieceType::Pawn && to.vert == last_vert {
+                    for promotion in self.variant.promotions() {
+                        result.push(Move::with_promotion(piece.position, to, promotion));
+                    }
+                } else {
+                    result.push(Move::new(piece.position, to));
+                }
+            }
+        }
+        if self.variant.has_pockets() {
+            let mut pocket = self.pocket(color).to_vec();
+            pocket.dedup();
+            for piece_type in pocket {
+                for to in self.squares() {
+                    if self.can_drop(piece_type, &to, color) {
+                        result.push(Move::with_drop(piece_type, to));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn try_move(&self, step: &Move, color: Color) -> Option<(Board, StepResultData)> {
+        let (next, result) = self.try_piece_move(step, color)?;
+        let is_duck_placed = match step.duck {
+            None => !self.variant.has_duck(),
+            Some(duck) => self.variant.has_duck() && self.is_duck_placeable(&next, &duck),
+        };
+        if is_duck_placed {
+            Some((next, result))
+        } else {
+            None
+        }
+    }
+
+    // Duck has to be moved to another square which is empty after the move
+    fn is_duck_placeable(&self, next: &Board, duck: &Position) -> bool {
+        self.contains(duck) && self.duck != Some(*duck) && next.cell(duck).is_none()
+    }
+
+    // Everything but the duck placement
+    fn try_piece_move(&self, step: &Move, color: Color) -> Option<(Board, StepResultData)> {
+        if !self.contains(&step.from) || !self.contains(&step.to) {
+            return None;
+        }
+        if let Some(piece_type) = step.drop {
+            if color != self.active_color || !self.can_drop(piece_type, &step.to, color) {
+                return None;
             }
+            return self.try_applied(step);
+        }
+        let piece = self.cell(&step.from).as_ref()?;
+        if piece.color != color || !piece.movement.can_move(piece, &step.to, self) {
+            return None;
+        }
+        let is_promotion =
+            piece.piece_type == PieceType::Pawn && step.to.vert == self.last_vertical(color);
+        match step.promotion {
+            None => {}
+            Some(_) if !is_promotion => return None,
+            Some(promotion) if !self.variant.promotions().contains(&promotion) => return None,
+            Some(_) => {}
+        }
+        self.try_applied(step)
+    }
+
+    fn try_applied(&self, step: &Move) -> Option<(Board, StepResultData)> {
+        let mut next = self.clone();
+        let result = next.apply_move(step);
+        if self.variant.is_legal(self, &next, &result) {
+            Some((next, result))
+        } else {
+            None
         }
-        None
     }
 
-    fn extract_piece(&mut self, position: &Position) -> Option<Piece> {
+    fn apply_move(&mut self, step: &Move) -> StepResultData {
+        let castling = self
+            .cell(&step.from)
+            .as_ref()
+            .and_then(|piece| self.castling_rook(piece, &step.to));
+        let mut piece = match step.drop {
+            Some(piece_type) => self.take_from_pocket(piece_type, &step.to),
+            None => self.extract_piece(&step.from).unwrap(),
+        };
+        let mut result = StepResultData {
+            step: *step,
+            piece_type: piece.piece_type,
+            color: piece.color,
+            eaten: None,
+            is_en_passant: false,
+            castling: None,
+            promotion: None,
+            exploded: Vec::new(),
+            checkers: Vec::new(),
+            outcome: None,
+        };
+        let (hor_diff, vert_diff) = &step.to - &step.from;
+        let is_pawn = piece.piece_type == PieceType::Pawn;
+        let mut destination = step.to;
+
+        if let Some((rook_position, side)) = castling {
+            let (king_to, rook_to) = self.castling_destinations(piece.color, side);
+            if let Some(mut rook) = self.extract_piece(&rook_position) {
+                rook.mark_as_moved();
+                rook.position = rook_to;
+                self.put_piece(rook);
+            }
+            destination = king_to;
+            result.castling = Some(side);
+        } else {
+            result.eaten = self.extract_piece(&step.to);
+        }
+
+        if is_pawn && hor_diff != 0 && Some(step.to) == self.en_passant {
+            let passed = Position {
+                hor: step.to.hor,
+                vert: step.from.vert,
+            };
+            result.eaten = self.extract_piece(&passed);
+            result.is_en_passant = true;
+        }
+        self.en_passant = if is_pawn && vert_diff.abs() == 2 {
+            step.from.transform(0, vert_diff / 2)
+        } else {
+            None
+        };
+
+        self.halfmove_clock = if is_pawn || result.eaten.is_some() {
+            0
+        } else {
+            self.halfmove_clock + 1
+        };
+        if piece.color == Color::Black {
+            self.fullmove_number += 1;
+        }
+
+        if step.drop.is_none() {
+            piece.mark_as_moved();
+        }
+        piece.position = destination;
+        if is_pawn && step.to.vert == self.last_vertical(piece.color) {
+            // Variant lists the strongest piece first, it is taken if nothing was asked for
+            let promotion = step.promotion.unwrap_or(self.variant.promotions()[0]);
+            result.promotion = Some(promotion);
+            piece = Piece::new(promotion, piece.color, step.to);
+            piece.mark_as_moved();
+            piece.mark_as_promoted();
+        }
+        let color = piece.color;
+        self.active_color = !color;
+        self.put_piece(piece);
+        if step.duck.is_some() {
+            self.duck = step.duck;
+        }
+        result.checkers = self
+            .checkers(!color)
+            .iter()
+            .map(|checker| checker.position)
+            .collect();
+        let variant = self.variant.clone();
+        variant.after_move(self, &mut result);
+        result
+    }
+
+    pub fn king_position(&self, color: Color) -> Option<Position> {
+        self.pieces()
+            .find(|piece| piece.color == color && piece.piece_type == PieceType::King)
+            .map(|king| king.position)
+    }
+
+    fn last_vertical(&self, color: Color) -> Vertical {
+        Board::relative_vertical(color, self.height - 1, self.height)
+    }
+
+    fn first_vertical(&self, color: Color) -> Vertical {
+        Board::relative_vertical(color, 0, self.height)
+    }
+
+    // Rank counted from the given side of the board, 0 is the first one
+    pub fn relative_vertical(color: Color, rank: u8, height: u8) -> Vertical {
+        let vert = match color {
+            Color::White => rank,
+            Color::Black => height - 1 - rank,
+        };
+        Position::at(0, vert as i8).unwrap().vert
+    }
+
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        (position.hor as u8) < self.width && (position.vert as u8) < self.height
+    }
+
+    // All squares of the board, rank by rank starting from A1
+    pub fn squares(&self) -> impl Iterator<Item = Position> {
+        let (width, height) = (self.width as i8, self.height as i8);
+        (0..width * height).filter_map(move |i| Position::at(i % width, i / width))
+    }
+
+    fn put_piece(&mut self, piece: Piece) {
+        let position = piece.position;
+        self.contents[position.vert as usize][position.hor as usize] = Some(piece);
+    }
+
+    pub fn extract_piece(&mut self, position: &Position) -> Option<Piece> {
         mem::replace(
             &mut self.contents[position.vert as usize][position.hor as usize],
             None,
@@ -121,47 +991,231 @@ impl Board {
     fn piece_icon(&self, i: usize, j: usize) -> char {
         match &self.contents[i][j] {
             Some(piece) => piece.icon(),
+            None if self.duck == Position::at(j as i8, i as i8) => DUCK_ICON,
             None => ' ',
         }
     }
+}
 
-    fn fill_board(board: &Rc<RefCell<Board>>) {
-        // reversed, whites on top (small letters), blacks on bottom (Capital letters)
-        let board_pic: [[u8; 8]; 8] = [
-            *b"rnbkqbnr",
-            *b"pppppppp",
-            *b"________",
-            *b"________",
-            *b"________",
-            *b"________",
-            *b"PPPPPPPP",
-            *b"RNBKQBNR",
-        ];
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{DrawReason, WinReason};
+    use crate::variants;
 
-        let mut borrowed = board.borrow_mut();
-
-        for i in 0..8 {
-            for j in 0..8 {
-                let piece_type = match board_pic[i][j].to_ascii_lowercase() {
-                    b'r' => PieceType::Rook,
-                    b'n' => PieceType::Knight,
-                    b'b' => PieceType::Bishop,
-                    b'k' => PieceType::King,
-                    b'q' => PieceType::Queen,
-                    b'p' => PieceType::Pawn,
-                    _ => continue,
-                };
-                let color = if board_pic[i][j].is_ascii_lowercase() {
-                    crate::common::Color::White
-                } else {
-                    crate::common::Color::Black
-                };
-                let position = Position {
-                    hor: FromPrimitive::from_usize(j).unwrap(),
-                    vert: FromPrimitive::from_usize(i).unwrap(),
-                };
-                borrowed.contents[i][j] = Some(Piece::new(piece_type, color, position));
-            }
+    fn square(name: &str) -> Position {
+        name.parse().unwrap()
+    }
+
+    #[test]
+    fn attackers_include_empty_and_defended_squares() {
+        let board = Board::from_fen("4k3/8/8/3p4/8/2N5/8/R3K3 w - - 0 1").unwrap();
+        let board = board.borrow();
+        let attackers = board.attackers_of(&square("d5"), Color::White);
+        assert_eq!(attackers.len(), 1);
+        assert_eq!(attackers[0].piece_type, PieceType::Knight);
+        assert_eq!(board.attackers_of(&square("a8"), Color::White).len(), 1);
+        assert_eq!(board.attackers_of(&square("a1"), Color::White).len(), 0);
+        assert_eq!(board.attackers_of(&square("e1"), Color::White).len(), 1);
+        let pawn_attacks = board.attackers_of(&square("c4"), Color::Black);
+        assert_eq!(pawn_attacks.len(), 1);
+        assert_eq!(pawn_attacks[0].piece_type, PieceType::Pawn);
+    }
+
+    #[test]
+    fn attacked_squares_of_lone_king() {
+        let board = Board::from_fen("k7/8/8/8/8/8/8/7K w - - 0 1").unwrap();
+        let mut attacked = board.borrow().attacked_squares(Color::White);
+        attacked.sort_by_key(|square| (square.vert, square.hor));
+        assert_eq!(attacked, vec![square("g1"), square("g2"), square("h2")]);
+    }
+
+    #[test]
+    fn kings_cannot_stand_next_to_each_other() {
+        let board = Board::from_fen("8/8/8/3k4/8/3K4/8/8 w - - 0 1").unwrap();
+        let moves = board.borrow().legal_moves(Color::White);
+        assert_eq!(moves.len(), 5);
+        assert!(moves.iter().all(|step| step.to.vert != Vertical::Vert4));
+    }
+
+    #[test]
+    fn castling_through_attacked_square_is_illegal() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/5r2/R3K2R w KQ - 0 1").unwrap();
+        let moves = board.borrow().legal_moves(Color::White);
+        assert!(!moves.contains(&Move::new(square("e1"), square("g1"))));
+        assert!(moves.contains(&Move::new(square("e1"), square("c1"))));
+    }
+
+    #[test]
+    fn discovered_and_double_checks_are_reported() {
+        let board = Board::from_fen("4k3/8/8/8/4N3/8/8/4R1K1 w - - 0 1").unwrap();
+
+        let mut discovered = board.borrow().clone();
+        let result = discovered
+            .move_piece(&Move::new(square("e4"), square("c3")), Color::White)
+            .unwrap();
+        assert_eq!(result.checkers, vec![square("e1")]);
+        assert!(!result.is_double_check());
+
+        let mut double = board.borrow().clone();
+        let result = double
+            .move_piece(&Move::new(square("e4"), square("d6")), Color::White)
+            .unwrap();
+        assert!(result.is_double_check());
+        assert_eq!(double.checkers(Color::Black).len(), 2);
+    }
+
+    #[test]
+    fn capture_with_check_keeps_all_details() {
+        let board = Board::from_fen("3qk3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+        let result = board
+            .borrow_mut()
+            .move_piece(&Move::new(square("d1"), square("d8")), Color::White)
+            .unwrap();
+        assert_eq!(
+            result.eaten.as_ref().map(|piece| piece.piece_type),
+            Some(PieceType::Queen)
+        );
+        assert_eq!(result.checkers, vec![square("d8")]);
+        assert_eq!(result.outcome, None);
+        assert!(!result.is_mate());
+    }
+
+    #[test]
+    fn captures_and_promotions_only() {
+        let board = Board::from_fen("3r2k1/1P6/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+        let board = board.borrow();
+        let captures = board.legal_captures(Color::White);
+        let all = board.legal_moves(Color::White);
+        assert_eq!(captures.len(), 1 + 4);
+        assert!(captures.iter().all(|step| all.contains(step)));
+        assert!(captures.contains(&Move::new(square("d1"), square("d8"))));
+        assert!(board.has_legal_moves(Color::White));
+        let stalemate = Board::from_fen("7k/8/6QK/8/8/8/8/8 b - - 0 1").unwrap();
+        assert!(!stalemate.borrow().has_legal_moves(Color::Black));
+    }
+
+    #[test]
+    fn game_outcomes() {
+        let mate = Board::from_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(
+            mate.borrow().game_outcome(),
+            Some(GameOutcome::Win(Color::White, WinReason::Checkmate))
+        );
+        let stalemate = Board::from_fen("7k/8/6QK/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(
+            stalemate.borrow().game_outcome(),
+            Some(GameOutcome::Draw(DrawReason::Stalemate))
+        );
+    }
+
+    #[test]
+    fn fen_round_trip() {
+        for fen in &[
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w Kq - 3 17",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ] {
+            assert_eq!(Board::from_fen(fen).unwrap().borrow().to_fen(), *fen);
+        }
+    }
+
+    #[test]
+    fn overlong_empty_square_counts_are_rejected() {
+        for fen in &[
+            "rnbqkbnr/999/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/99999999999999999999/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/9/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/p7p/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        ] {
+            assert!(Board::from_fen(fen).is_err());
         }
     }
+
+    fn targets(fen: &str, from: &str) -> Vec<Position> {
+        let board = Board::from_fen(fen).unwrap();
+        let board = board.borrow();
+        let from = square(from);
+        board
+            .legal_moves(Color::White)
+            .iter()
+            .filter(|step| step.from == from)
+            .map(|step| step.to)
+            .collect()
+    }
+
+    #[test]
+    fn fairy_pieces_in_fen() {
+        let fen = "k7/8/8/3a4/2C5/1m6/l7/K5Gg w - - 0 1";
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(board.borrow().to_fen(), fen);
+        let icons: String = board.borrow().pieces().map(|piece| piece.icon()).collect();
+        assert_eq!(
+            icons,
+            "\u{2654}\u{1FA01}\u{1FA07}\u{1FA0A}\u{1FA53}\u{1FA51}\u{1FA55}\u{265A}"
+        );
+    }
+
+    #[test]
+    fn compound_pieces_add_knight_jumps() {
+        assert_eq!(targets("2k5/8/8/8/8/8/8/A6K w - - 0 1", "a1").len(), 7 + 2);
+        assert_eq!(
+            targets("2k5/8/8/8/8/8/8/C6K w - - 0 1", "a1").len(),
+            7 + 6 + 2
+        );
+        assert_eq!(
+            targets("2k5/8/8/8/8/8/8/M6K w - - 0 1", "a1").len(),
+            7 + 7 + 6 + 2
+        );
+        let camel = targets("2k5/8/8/8/8/8/8/L6K w - - 0 1", "a1");
+        assert_eq!(camel, vec![square("d2"), square("b4")]);
+    }
+
+    #[test]
+    fn grasshopper_lands_right_behind_the_hurdle() {
+        assert_eq!(
+            targets("2k5/8/8/8/8/8/8/G1nr3K w - - 0 1", "a1"),
+            vec![square("d1")]
+        );
+        assert!(targets("2k5/8/8/8/8/8/8/G1nR3K w - - 0 1", "a1").is_empty());
+        assert_eq!(
+            targets("2k5/8/8/8/8/8/1p6/G6K w - - 0 1", "a1"),
+            vec![square("c3")]
+        );
+    }
+
+    fn chess960_board(fen: &str) -> ChessResult<Rc<RefCell<Board>>> {
+        Board::from_variant_fen(fen, variants::create("chess960", 518)?)
+    }
+
+    #[test]
+    fn chess960_fen_forms() {
+        let shredder = "1r2k1r1/8/8/8/8/8/8/RR2K3 w Bg - 0 1";
+        let board = chess960_board(shredder).unwrap();
+        assert_eq!(
+            board.borrow().to_fen(),
+            "1r2k1r1/8/8/8/8/8/8/RR2K3 w Bk - 0 1"
+        );
+        let x_fen = chess960_board("1r2k1r1/8/8/8/8/8/8/RR2K3 w Bk - 0 1").unwrap();
+        assert_eq!(x_fen.borrow().to_fen(), board.borrow().to_fen());
+    }
+
+    #[test]
+    fn chess960_castling() {
+        let board = chess960_board("4k3/8/8/8/8/8/8/1R4KR w BH - 0 1").unwrap();
+        let king_side = board
+            .borrow()
+            .castling_move(Color::White, CastlingSide::King);
+        assert_eq!(king_side, Some(Move::new(square("g1"), square("h1"))));
+        let queen_side = board
+            .borrow()
+            .castling_move(Color::White, CastlingSide::Queen)
+            .unwrap();
+        let result = board
+            .borrow_mut()
+            .move_piece(&queen_side, Color::White)
+            .unwrap();
+        assert_eq!(result.castling, Some(CastlingSide::Queen));
+        assert_eq!(board.borrow().to_fen(), "4k3/8/8/8/8/8/8/2KR3R b - - 1 1");
+    }
 }
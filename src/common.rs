@@ -1,4 +1,5 @@
-use crate::pieces::Piece;
+use crate::moves::Move;
+use crate::pieces::{Piece, PieceType};
 use crate::positions;
 
 #[derive(Debug)]
@@ -8,12 +9,69 @@ pub enum ChessError {
     InvalidInput(String),
     EmptyCell(positions::Position),
     WrongColor(positions::Position),
+    CaptureRequired(positions::Position, positions::Position),
+    DuckRequired(positions::Position, positions::Position),
 }
 
-pub enum StepResultData {
-    Moved,
-    Eaten(Piece),
-    Check(Color),
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CastlingSide {
+    King,
+    Queen,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum WinReason {
+    Checkmate,
+    KingOfTheHill,
+    ThreeChecks,
+    KingExploded,
+    AllPiecesLost,
+    Stalemated,
+    KingReachedGoal,
+    AllPiecesCaptured,
+    KingCaptured,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DrawReason {
+    Stalemate,
+    BothKingsReachedGoal,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GameOutcome {
+    Win(Color, WinReason),
+    Draw(DrawReason),
+}
+
+// Everything that happened during a single move
+pub struct StepResultData {
+    pub step: Move,
+    pub piece_type: PieceType,
+    pub color: Color,
+    pub eaten: Option<Piece>,
+    pub is_en_passant: bool,
+    pub castling: Option<CastlingSide>,
+    pub promotion: Option<PieceType>,
+    // Pieces removed by the variant rules besides the eaten one, like Atomic explosions
+    pub exploded: Vec<Piece>,
+    // Positions of all pieces giving check to the opponent after the move
+    pub checkers: Vec<positions::Position>,
+    pub outcome: Option<GameOutcome>,
+}
+
+impl StepResultData {
+    pub fn is_check(&self) -> bool {
+        !self.checkers.is_empty()
+    }
+
+    pub fn is_double_check(&self) -> bool {
+        self.checkers.len() > 1
+    }
+
+    pub fn is_mate(&self) -> bool {
+        self.outcome == Some(GameOutcome::Win(self.color, WinReason::Checkmate))
+    }
 }
 
 pub type ChessResult<T> = std::result::Result<T, ChessError>;
@@ -45,12 +103,58 @@ impl std::fmt::Display for ChessError {
             InvalidInput(input) => write!(f, "Invalid input: {}", input),
             EmptyCell(pos) => write!(f, "No piece on {}", pos),
             WrongColor(pos) => write!(f, "Wrong color on {}", pos),
+            CaptureRequired(from, to) => {
+                write!(f, "Can't move from {} to {}, capture is required", from, to)
+            }
+            DuckRequired(from, to) => write!(
+                f,
+                "Can't move from {} to {}, duck has to be placed too (like {}-{},d5)",
+                from, to, from, to
+            ),
         }
     }
 }
 
 impl std::error::Error for ChessError {}
 
+impl std::fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameOutcome::Win(color, WinReason::Checkmate) => {
+                write!(f, "{:?} wins by checkmate", color)
+            }
+            GameOutcome::Win(color, WinReason::KingOfTheHill) => {
+                write!(f, "{:?} wins by reaching the center", color)
+            }
+            GameOutcome::Win(color, WinReason::ThreeChecks) => {
+                write!(f, "{:?} wins by giving the third check", color)
+            }
+            GameOutcome::Win(color, WinReason::KingExploded) => {
+                write!(f, "{:?} wins by exploding the king", color)
+            }
+            GameOutcome::Win(color, WinReason::AllPiecesLost) => {
+                write!(f, "{:?} wins by losing all pieces", color)
+            }
+            GameOutcome::Win(color, WinReason::Stalemated) => {
+                write!(f, "{:?} wins by being stalemated", color)
+            }
+            GameOutcome::Win(color, WinReason::KingReachedGoal) => {
+                write!(f, "{:?} wins by reaching the last rank", color)
+            }
+            GameOutcome::Win(color, WinReason::AllPiecesCaptured) => {
+                write!(f, "{:?} wins by capturing all pieces", color)
+            }
+            GameOutcome::Win(color, WinReason::KingCaptured) => {
+                write!(f, "{:?} wins by capturing the king", color)
+            }
+            GameOutcome::Draw(DrawReason::Stalemate) => write!(f, "Draw by stalemate"),
+            GameOutcome::Draw(DrawReason::BothKingsReachedGoal) => {
+                write!(f, "Draw, both kings reached the last rank")
+            }
+        }
+    }
+}
+
 impl std::ops::Not for Color {
     type Output = Color;
     fn not(self) -> Color {
@@ -0,0 +1,502 @@
+use crate::board::Board;
+use crate::common::Color;
+use crate::engine::Score;
+use crate::pieces::{Piece, PieceType};
+use crate::positions::{Position, MAX_SIZE};
+
+// Phase of the game with all the pieces on the board, it goes down to 0 in bare endgames
+const MAX_PHASE: Score = 24;
+
+// Tables are written from white's point of view with the eighth rank on top, like a diagram.
+// Other board sizes are scaled to them
+type Table = [[Score; 8]; 8];
+
+#[rustfmt::skip]
+const PAWN_MIDDLEGAME: Table = [
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [ 50,  50,  50,  50,  50,  50,  50,  50],
+    [ 10,  10,  20,  30,  30,  20,  10,  10],
+    [  5,   5,  10,  25,  25,  10,   5,   5],
+    [  0,   0,   0,  20,  20,   0,   0,   0],
+    [  5,  -5, -10,   0,   0, -10,  -5,   5],
+    [  5,  10,  10, -20, -20,  10,  10,   5],
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+];
+
+#[rustfmt::skip]
+const PAWN_ENDGAME: Table = [
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [ 60,  60,  60,  60,  60,  60,  60,  60],
+    [ 40,  40,  40,  40,  40,  40,  40,  40],
+    [ 25,  25,  25,  25,  25,  25,  25,  25],
+    [ 10,  10,  10,  10,  10,  10,  10,  10],
+    [  5,   5,   5,   5,   5,   5,   5,   5],
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+];
+
+#[rustfmt::skip]
+const KNIGHT: Table = [
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+    [-40, -20,   0,   0,   0,   0, -20, -40],
+    [-30,   0,  10,  15,  15,  10,   0, -30],
+    [-30,   5,  15,  20,  20,  15,   5, -30],
+    [-30,   0,  15,  20,  20,  15,   0, -30],
+    [-30,   5,  10,  15,  15,  10,   5, -30],
+    [-40, -20,   0,   5,   5,   0, -20, -40],
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+];
+
+#[rustfmt::skip]
+const BISHOP: Table = [
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+    [-10,   0,   0,   0,   0,   0,   0, -10],
+    [-10,   0,   5,  10,  10,   5,   0, -10],
+    [-10,   5,   5,  10,  10,   5,   5, -10],
+    [-10,   0,  10,  10,  10,  10,   0, -10],
+    [-10,  10,  10,  10,  10,  10,  10, -10],
+    [-10,   5,   0,   0,   0,   0,   5, -10],
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+];
+
+#[rustfmt::skip]
+const ROOK: Table = [
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [  5,  10,  10,  10,  10,  10,  10,   5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [  0,   0,   0,   5,   5,   0,   0,   0],
+];
+
+#[rustfmt::skip]
+const QUEEN: Table = [
+    [-20, -10, -10,  -5,  -5, -10, -10, -20],
+    [-10,   0,   0,   0,   0,   0,   0, -10],
+    [-10,   0,   5,   5,   5,   5,   0, -10],
+    [ -5,   0,   5,   5,   5,   5,   0,  -5],
+    [  0,   0,   5,   5,   5,   5,   0,  -5],
+    [-10,   5,   5,   5,   5,   5,   0, -10],
+    [-10,   0,   5,   0,   0,   0,   0, -10],
+    [-20, -10, -10,  -5,  -5, -10, -10, -20],
+];
+
+#[rustfmt::skip]
+const KING_MIDDLEGAME: Table = [
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-20, -30, -30, -40, -40, -30, -30, -20],
+    [-10, -20, -20, -20, -20, -20, -20, -10],
+    [ 20,  20,   0,   0,   0,   0,  20,  20],
+    [ 20,  30,  10,   0,   0,  10,  30,  20],
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME: Table = [
+    [-50, -40, -30, -20, -20, -30, -40, -50],
+    [-30, -20, -10,   0,   0, -10, -20, -30],
+    [-30, -10,  20,  30,  30,  20, -10, -30],
+    [-30, -10,  30,  40,  40,  30, -10, -30],
+    [-30, -10,  30,  40,  40,  30, -10, -30],
+    [-30, -10,  20,  30,  30,  20, -10, -30],
+    [-30, -30,   0,   0,   0,   0, -30, -30],
+    [-50, -30, -30, -30, -30, -30, -30, -50],
+];
+
+// Bonus of a passed pawn by its rank counted from its own side
+const PASSED_PAWN: [Tapered; 8] = [
+    Tapered::new(0, 0),
+    Tapered::new(5, 10),
+    Tapered::new(10, 20),
+    Tapered::new(15, 35),
+    Tapered::new(25, 60),
+    Tapered::new(40, 95),
+    Tapered::new(70, 140),
+    Tapered::new(0, 0),
+];
+const DOUBLED_PAWN: Tapered = Tapered::new(-10, -20);
+const ISOLATED_PAWN: Tapered = Tapered::new(-10, -15);
+// Own pawn in front of the king and enemy attack next to it
+const PAWN_SHIELD: Score = 10;
+const KING_ZONE_ATTACK: Score = -10;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Term {
+    Material,
+    PieceSquares,
+    PawnStructure,
+    Mobility,
+    KingSafety,
+}
+
+pub const TERMS: [Term; 5] = [
+    Term::Material,
+    Term::PieceSquares,
+    Term::PawnStructure,
+    Term::Mobility,
+    Term::KingSafety,
+];
+
+// Middlegame and endgame values of a term, blended according to the game phase
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Tapered {
+    pub middlegame: Score,
+    pub endgame: Score,
+}
+
+impl Tapered {
+    pub const fn new(middlegame: Score, endgame: Score) -> Self {
+        Tapered {
+            middlegame,
+            endgame,
+        }
+    }
+}
+
+impl std::ops::AddAssign for Tapered {
+    fn add_assign(&mut self, other: Tapered) {
+        self.middlegame += other.middlegame;
+        self.endgame += other.endgame;
+    }
+}
+
+impl std::ops::Mul<Score> for Tapered {
+    type Output = Tapered;
+    fn mul(self, times: Score) -> Tapered {
+        Tapered::new(self.middlegame * times, self.endgame * times)
+    }
+}
+
+// All terms for both sides, so it can be shown how the score was made up
+pub struct Evaluation {
+    terms: [[Tapered; 2]; TERMS.len()],
+    phase: Score,
+}
+
+impl Evaluation {
+    pub fn phase(&self) -> Score {
+        self.phase
+    }
+
+    pub fn raw_term(&self, term: Term, color: Color) -> Tapered {
+        self.terms[term as usize][color as usize]
+    }
+
+    pub fn term(&self, term: Term, color: Color) -> Score {
+        let value = self.raw_term(term, color);
+        (value.middlegame * self.phase + value.endgame * (MAX_PHASE - self.phase)) / MAX_PHASE
+    }
+
+    // Difference between white and black, positive if white is better
+    pub fn balance(&self, term: Term) -> Score {
+        self.term(term, Color::White) - self.term(term, Color::Black)
+    }
+
+    pub fn total(&self) -> Score {
+        TERMS.iter().map(|term| self.balance(*term)).sum()
+    }
+
+    fn add(&mut self, term: Term, color: Color, value: Tapered) {
+        self.terms[term as usize][color as usize] += value;
+    }
+}
+
+impl std::fmt::Display for Term {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Term::Material => "Material",
+            Term::PieceSquares => "Piece squares",
+            Term::PawnStructure => "Pawn structure",
+            Term::Mobility => "Mobility",
+            Term::KingSafety => "King safety",
+        };
+        f.pad(name)
+    }
+}
+
+// Table of all terms in pawns
+impl std::fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pawns = |score: Score| f64::from(score) / 100.0;
+        writeln!(
+            f,
+            "{:<16}{:>8}{:>8}{:>8}",
+            "Term", "White", "Black", "Total"
+        )?;
+        for term in TERMS.iter() {
+            writeln!(
+                f,
+                "{:<16}{:>8.2}{:>8.2}{:>+8.2}",
+                term,
+                pawns(self.term(*term, Color::White)),
+                pawns(self.term(*term, Color::Black)),
+                pawns(self.balance(*term))
+            )?;
+        }
+        write!(
+            f,
+            "{:<16}{:>24}",
+            format!("Phase {}/{}", self.phase(), MAX_PHASE),
+            format!("{:+.2}", pawns(self.total()))
+        )
+    }
+}
+
+// Middlegame material value, used to weigh captures against each other
+pub fn piece_value(piece_type: PieceType) -> Score {
+    material(piece_type).middlegame
+}
+
+// King is never traded, so it doesn't count as material
+fn material(piece_type: PieceType) -> Tapered {
+    match piece_type {
+        PieceType::Pawn => Tapered::new(100, 120),
+        PieceType::Knight => Tapered::new(320, 300),
+        PieceType::Bishop => Tapered::new(330, 320),
+        PieceType::Rook => Tapered::new(500, 550),
+        PieceType::Queen => Tapered::new(900, 950),
+        PieceType::King => Tapered::new(0, 0),
+        PieceType::Archbishop => Tapered::new(850, 820),
+        PieceType::Chancellor => Tapered::new(900, 950),
+        PieceType::Amazon => Tapered::new(1200, 1250),
+        PieceType::Camel => Tapered::new(250, 230),
+        PieceType::Grasshopper => Tapered::new(200, 180),
+    }
+}
+
+// How much the piece brings the game away from the endgame
+fn phase_weight(piece_type: PieceType) -> Score {
+    match piece_type {
+        PieceType::Pawn | PieceType::King => 0,
+        PieceType::Knight | PieceType::Bishop | PieceType::Camel | PieceType::Grasshopper => 1,
+        PieceType::Rook => 2,
+        PieceType::Archbishop => 3,
+        PieceType::Queen | PieceType::Chancellor => 4,
+        PieceType::Amazon => 6,
+    }
+}
+
+// Compound and fairy pieces use the table of the piece closest to them
+fn tables(piece_type: PieceType) -> (&'static Table, &'static Table) {
+    match piece_type {
+        PieceType::Pawn => (&PAWN_MIDDLEGAME, &PAWN_ENDGAME),
+        PieceType::Knight | PieceType::Archbishop | PieceType::Camel => (&KNIGHT, &KNIGHT),
+        PieceType::Bishop => (&BISHOP, &BISHOP),
+        PieceType::Rook | PieceType::Chancellor => (&ROOK, &ROOK),
+        PieceType::Queen | PieceType::Amazon | PieceType::Grasshopper => (&QUEEN, &QUEEN),
+        PieceType::King => (&KING_MIDDLEGAME, &KING_ENDGAME),
+    }
+}
+
+// Rank counted from the piece's own side
+fn relative_rank(board: &Board, position: &Position, color: Color) -> Score {
+    match color {
+        Color::White => position.vert as Score,
+        Color::Black => Score::from(board.height()) - 1 - position.vert as Score,
+    }
+}
+
+// Scales board coordinate to 0..7 for the tables
+fn scaled(value: Score, size: u8) -> usize {
+    (value * 7 / (Score::from(size) - 1).max(1)) as usize
+}
+
+fn piece_square(board: &Board, piece: &Piece) -> Tapered {
+    let row = 7 - scaled(
+        relative_rank(board, &piece.position, piece.color),
+        board.height(),
+    );
+    let column = scaled(piece.position.hor as Score, board.width());
+    let (middlegame, endgame) = tables(piece.piece_type);
+    Tapered::new(middlegame[row][column], endgame[row][column])
+}
+
+fn pawn_structure(board: &Board, evaluation: &mut Evaluation) {
+    let pawns = |color: Color| -> Vec<&Piece> {
+        board
+            .pieces()
+            .filter(|piece| piece.color == color && piece.piece_type == PieceType::Pawn)
+            .collect()
+    };
+    for color in [Color::White, Color::Black].iter() {
+        let own = pawns(*color);
+        let enemy = pawns(!*color);
+        let mut files = [0; MAX_SIZE as usize];
+        for pawn in own.iter() {
+            files[pawn.position.hor as usize] += 1;
+        }
+        for count in files.iter().filter(|count| **count > 1) {
+            evaluation.add(Term::PawnStructure, *color, DOUBLED_PAWN * (count - 1));
+        }
+        // Same or adjacent file
+        let is_near =
+            |file: usize, other: &Piece| (other.position.hor as i32 - file as i32).abs() <= 1;
+        for pawn in own.iter() {
+            let file = pawn.position.hor as usize;
+            let has_neighbours = own
+                .iter()
+                .any(|other| other.position.hor as usize != file && is_near(file, other));
+            if !has_neighbours {
+                evaluation.add(Term::PawnStructure, *color, ISOLATED_PAWN);
+            }
+            let rank = relative_rank(board, &pawn.position, *color);
+            let is_passed = !enemy.iter().any(|other| {
+                is_near(file, other) && relative_rank(board, &other.position, *color) > rank
+            });
+            if is_passed {
+                let bonus = PASSED_PAWN[scaled(rank, board.height())];
+                evaluation.add(Term::PawnStructure, *color, bonus);
+            }
+        }
+    }
+}
+
+// Squares the piece can move to, pawns and kings don't count
+fn mobility(board: &Board, piece: &Piece) -> Tapered {
+    let per_move = match piece.piece_type {
+        PieceType::Pawn | PieceType::King => return Tapered::default(),
+        PieceType::Knight | PieceType::Camel => Tapered::new(4, 4),
+        PieceType::Bishop => Tapered::new(5, 5),
+        PieceType::Rook => Tapered::new(2, 4),
+        PieceType::Archbishop => Tapered::new(3, 3),
+        PieceType::Chancellor | PieceType::Grasshopper => Tapered::new(2, 3),
+        PieceType::Queen | PieceType::Amazon => Tapered::new(1, 2),
+    };
+    let moves = board
+        .squares()
+        .filter(|to| piece.movement.can_move(piece, to, board))
+        .count();
+    per_move * moves as Score
+}
+
+// Pawns sheltering the king and enemy attacks around it, only matters in the middlegame
+fn king_safety(board: &Board, color: Color) -> Tapered {
+    let king = match board.king_position(color) {
+        Some(king) => king,
+        None => return Tapered::default(),
+    };
+    let forward = if color == Color::White { 1 } else { -1 };
+    let mut safety = 0;
+    for hor in -1..=1 {
+        for vert in -1..=1 {
+            let square = match Position::at(king.hor as i8 + hor, king.vert as i8 + vert) {
+                Some(square) if square != king && board.contains(&square) => square,
+                _ => continue,
+            };
+            if !board.attackers_of(&square, !color).is_empty() {
+                safety += KING_ZONE_ATTACK;
+            }
+        }
+        for distance in 1..=2 {
+            let square = Position::at(king.hor as i8 + hor, king.vert as i8 + forward * distance);
+            let is_shield = square
+                .filter(|square| board.contains(square))
+                .and_then(|square| board.cell(&square).as_ref())
+                .map(|piece| piece.color == color && piece.piece_type == PieceType::Pawn)
+                .unwrap_or(false);
+            if is_shield {
+                safety += PAWN_SHIELD;
+            }
+        }
+    }
+    Tapered::new(safety, 0)
+}
+
+pub fn explain(board: &Board) -> Evaluation {
+    let mut evaluation = Evaluation {
+        terms: Default::default(),
+        phase: 0,
+    };
+    let mut phase = 0;
+    for piece in board.pieces() {
+        phase += phase_weight(piece.piece_type);
+        evaluation.add(Term::Material, piece.color, material(piece.piece_type));
+        evaluation.add(Term::PieceSquares, piece.color, piece_square(board, piece));
+        evaluation.add(Term::Mobility, piece.color, mobility(board, piece));
+    }
+    for color in [Color::White, Color::Black].iter() {
+        for piece_type in board.pocket(*color) {
+            phase += phase_weight(*piece_type);
+            evaluation.add(Term::Material, *color, material(*piece_type));
+        }
+        evaluation.add(Term::KingSafety, *color, king_safety(board, *color));
+    }
+    pawn_structure(board, &mut evaluation);
+    evaluation.phase = phase.min(MAX_PHASE);
+    evaluation
+}
+
+// Score for the side to move
+pub fn evaluate(board: &Board) -> Score {
+    let total = explain(board).total();
+    match board.active_color() {
+        Color::White => total,
+        Color::Black => -total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explained(fen: &str) -> Evaluation {
+        explain(&Board::from_fen(fen).unwrap().borrow())
+    }
+
+    #[test]
+    fn initial_position_is_balanced() {
+        let evaluation = explain(&Board::new().borrow());
+        for term in TERMS.iter() {
+            assert_eq!(evaluation.balance(*term), 0, "{}", term);
+        }
+        assert_eq!(evaluation.phase(), MAX_PHASE);
+        assert!(evaluation.term(Term::Mobility, Color::White) > 0);
+        assert!(evaluation.term(Term::KingSafety, Color::White) > 0);
+    }
+
+    #[test]
+    fn score_is_from_side_to_move() {
+        let white = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+        let black = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+        assert!(evaluate(&white.borrow()) > 900);
+        assert_eq!(evaluate(&white.borrow()), -evaluate(&black.borrow()));
+    }
+
+    #[test]
+    fn pawn_structure_terms() {
+        // Doubled and isolated c pawns against a healthy pair
+        let weak = explained("4k3/5pp1/8/8/8/2P5/2P5/4K3 w - - 0 1");
+        let doubled_isolated = (DOUBLED_PAWN * 1).endgame + (ISOLATED_PAWN * 2).endgame;
+        let passed = PASSED_PAWN[1].endgame + PASSED_PAWN[2].endgame;
+        assert_eq!(
+            weak.raw_term(Term::PawnStructure, Color::White).endgame,
+            doubled_isolated + passed
+        );
+        // Black pawns are passed too, but not isolated
+        let black_passed = PASSED_PAWN[1].endgame * 2;
+        assert_eq!(
+            weak.raw_term(Term::PawnStructure, Color::Black).endgame,
+            black_passed
+        );
+        let blocked = explained("4k3/8/3p4/8/2P5/8/8/4K3 w - - 0 1");
+        assert_eq!(
+            blocked.raw_term(Term::PawnStructure, Color::White),
+            ISOLATED_PAWN
+        );
+    }
+
+    #[test]
+    fn tapered_by_phase() {
+        let endgame = explained("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+        assert_eq!(endgame.phase(), 0);
+        let king = endgame.raw_term(Term::PieceSquares, Color::White);
+        assert_eq!(endgame.term(Term::PieceSquares, Color::White), king.endgame);
+        let open_king = explained("4k3/8/8/8/8/8/8/r3K3 w - - 0 1");
+        let safety = open_king.raw_term(Term::KingSafety, Color::White);
+        assert_eq!(safety, Tapered::new(KING_ZONE_ATTACK, 0));
+    }
+}
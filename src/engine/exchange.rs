@@ -0,0 +1,149 @@
+use crate::board::Board;
+use crate::engine::{piece_value, Score};
+use crate::moves::Move;
+use crate::pieces::PieceType;
+use crate::positions::Position;
+
+// King can take part in the exchange only as the last one, losing it costs everything
+fn exchange_value(piece_type: PieceType) -> Score {
+    match piece_type {
+        PieceType::King => 20_000,
+        _ => piece_value(piece_type),
+    }
+}
+
+// Piece taken by the move, en passant captures take a pawn from an empty square
+pub fn captured_piece(board: &Board, step: &Move) -> Option<PieceType> {
+    if step.drop.is_some() || !board.is_capture(step) {
+        return None;
+    }
+    Some(
+        board
+            .cell(&step.to)
+            .as_ref()
+            .map(|piece| piece.piece_type)
+            .unwrap_or(PieceType::Pawn),
+    )
+}
+
+// Most valuable victim first, the least valuable attacker among the same victims
+pub fn mvv_lva(board: &Board, step: &Move) -> Score {
+    let victim = match captured_piece(board, step) {
+        Some(victim) => victim,
+        None => return 0,
+    };
+    let attacker = board
+        .cell(&step.from)
+        .as_ref()
+        .map(|piece| exchange_value(piece.piece_type))
+        .unwrap_or(0);
+    piece_value(victim) * 10 - attacker / 10
+}
+
+// Static exchange evaluation: material won or lost if both sides keep capturing on the target
+// square with their least valuable pieces. Pins, checks and promotions are not taken into account
+pub fn see(board: &Board, step: &Move) -> Score {
+    let captured = match captured_piece(board, step) {
+        Some(captured) => captured,
+        None => return 0,
+    };
+    let (mut on_square, mut color) = match board.cell(&step.from) {
+        Some(piece) => (exchange_value(piece.piece_type), !piece.color),
+        None => return 0,
+    };
+    let mut board = board.clone();
+    board.extract_piece(&step.from);
+    if board.extract_piece(&step.to).is_none() {
+        // En passant, the taken pawn stands next to the capturing one
+        board.extract_piece(&Position {
+            hor: step.to.hor,
+            vert: step.from.vert,
+        });
+    }
+
+    let mut gains = vec![piece_value(captured)];
+    loop {
+        let attacker = board
+            .attackers_of(&step.to, color)
+            .into_iter()
+            .min_by_key(|piece| exchange_value(piece.piece_type))
+            .map(|piece| (piece.position, exchange_value(piece.piece_type)));
+        let (from, value) = match attacker {
+            Some(attacker) => attacker,
+            None => break,
+        };
+        gains.push(on_square - gains[gains.len() - 1]);
+        on_square = value;
+        board.extract_piece(&from);
+        color = !color;
+    }
+    // Each side can stop capturing when going on doesn't pay off
+    while gains.len() > 1 {
+        let last = gains.pop().unwrap_or_default();
+        let previous = gains.len() - 1;
+        gains[previous] = -(-gains[previous]).max(last);
+    }
+    gains[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::MAX_MATE_PLIES;
+
+    fn see_of(fen: &str, step: &str) -> Score {
+        let board = Board::from_fen(fen).unwrap();
+        let result = see(&board.borrow(), &step.parse().unwrap());
+        result
+    }
+
+    #[test]
+    fn simple_exchanges() {
+        // Undefended pawn
+        assert_eq!(
+            see_of("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "e1-e5"),
+            100
+        );
+        // Queen takes pawn defended by pawn
+        assert_eq!(see_of("4k3/8/3p4/4p3/8/8/7Q/4K3 w - - 0 1", "h2-e5"), -800);
+        // Pawn takes defended knight
+        assert_eq!(see_of("4k3/8/3p4/4n3/3P4/8/8/4K3 w - - 0 1", "d4-e5"), 220);
+        assert_eq!(see_of("4k3/8/8/8/8/8/8/4K3 w - - 0 1", "e1-e2"), 0);
+    }
+
+    #[test]
+    fn sliders_behind_are_counted() {
+        // Knight is lost to the longer chain of defenders
+        let fen = "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1";
+        assert!(see_of(fen, "d3-e5") < 0);
+        // Doubled rooks win the pawn defended by a rook
+        assert_eq!(see_of("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", "d2-d5"), 100);
+    }
+
+    #[test]
+    fn king_recaptures_only_unprotected_pieces() {
+        assert_eq!(see_of("8/8/4k3/3n4/8/3R4/8/4K3 w - - 0 1", "d3-d5"), -180);
+        assert_eq!(see_of("8/8/4k3/3n4/8/3R4/8/3RK3 w - - 0 1", "d3-d5"), 320);
+        assert_eq!(see_of("4k3/8/8/8/8/4p3/3K4/8 w - - 0 1", "d2-e3"), 100);
+        assert!(see_of("4k3/8/8/8/5p2/4p3/3K4/8 w - - 0 1", "d2-e3") < -MAX_MATE_PLIES);
+    }
+
+    #[test]
+    fn en_passant_and_ordering() {
+        let fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1";
+        let board = Board::from_fen(fen).unwrap();
+        let step = "e5-d6".parse().unwrap();
+        assert_eq!(
+            captured_piece(&board.borrow(), &step),
+            Some(PieceType::Pawn)
+        );
+        assert_eq!(see(&board.borrow(), &step), 100);
+        let board = Board::from_fen("Q7/7k/8/3q4/2P1r3/3P4/8/4K3 w - - 0 1").unwrap();
+        let board = board.borrow();
+        let pawn_takes_queen = mvv_lva(&board, &"c4-d5".parse().unwrap());
+        let queen_takes_queen = mvv_lva(&board, &"a8-d5".parse().unwrap());
+        let pawn_takes_rook = mvv_lva(&board, &"d3-e4".parse().unwrap());
+        assert!(pawn_takes_queen > queen_takes_queen);
+        assert!(queen_takes_queen > pawn_takes_rook);
+    }
+}
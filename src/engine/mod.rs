@@ -0,0 +1,48 @@
+mod evaluation;
+mod exchange;
+mod ordering;
+mod search;
+mod time_management;
+mod transposition;
+mod zobrist;
+
+pub use evaluation::{evaluate, explain, piece_value};
+pub use search::{Engine, SearchLimits, SearchResult};
+pub use time_management::time_for_move;
+
+// Centipawns from the point of view of the side to move
+pub type Score = i32;
+
+// Won game is scored as MATE minus the number of plies to it, so faster wins are preferred
+pub const MATE: Score = 100_000;
+pub const INFINITY: Score = MATE + 1;
+// Scores closer to MATE than this are forced mates
+pub const MAX_MATE_PLIES: Score = 1000;
+// Transposition table size when nothing else is asked for
+pub const DEFAULT_HASH_MB: usize = 16;
+// Larger tables are cut down to this size
+pub const MAX_HASH_MB: usize = 65536;
+
+// Pawns, or moves till mate like #3 (#-3 if it is the side to move who gets mated)
+pub fn format_score(score: Score) -> String {
+    if score.abs() > MATE - MAX_MATE_PLIES {
+        let moves = (MATE - score.abs() + 1) / 2;
+        format!("#{}", if score > 0 { moves } else { -moves })
+    } else {
+        format!("{:+.2}", f64::from(score) / 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_in_pawns_and_mates() {
+        assert_eq!(format_score(35), "+0.35");
+        assert_eq!(format_score(-120), "-1.20");
+        assert_eq!(format_score(MATE - 1), "#1");
+        assert_eq!(format_score(MATE - 5), "#3");
+        assert_eq!(format_score(-(MATE - 2)), "#-1");
+    }
+}
@@ -0,0 +1,158 @@
+use crate::board::Board;
+use crate::common::Color;
+use crate::engine::exchange::{mvv_lva, see};
+use crate::engine::{piece_value, Score};
+use crate::moves::Move;
+use crate::positions::{Position, MAX_SIZE};
+
+// Killer moves are remembered for this many plies from the root
+const MAX_PLY: usize = 128;
+// Enough for every board the variants use
+const SQUARES: usize = MAX_SIZE as usize * MAX_SIZE as usize;
+
+const TABLE_MOVE: Score = 1_000_000;
+const GOOD_CAPTURE: Score = 500_000;
+const PROMOTION: Score = 400_000;
+const KILLER: Score = 300_000;
+// History scores are halved between searches and kept below the killers
+const MAX_HISTORY: Score = 200_000;
+const BAD_CAPTURE: Score = -500_000;
+
+// Decides which moves are searched first, good ones found early give more cutoffs
+pub struct MoveOrdering {
+    // Two quiet moves per ply that caused a cutoff in a sibling position
+    killers: Vec<[Option<Move>; 2]>,
+    // How often a quiet move caused a cutoff anywhere in the tree, by color, from and to
+    history: Vec<Score>,
+}
+
+impl MoveOrdering {
+    pub fn new() -> Self {
+        MoveOrdering {
+            killers: vec![[None; 2]; MAX_PLY],
+            history: vec![0; 2 * SQUARES * SQUARES],
+        }
+    }
+
+    // Killers are specific to positions of the previous search, history is only aged
+    pub fn new_search(&mut self) {
+        self.killers
+            .iter_mut()
+            .for_each(|killers| *killers = [None; 2]);
+        self.history.iter_mut().for_each(|score| *score /= 2);
+    }
+
+    // Table move, winning and even captures by MVV-LVA, promotions, killers,
+    // quiet moves by history and losing captures last
+    pub fn order(
+        &self,
+        board: &Board,
+        mut moves: Vec<Move>,
+        table_move: Option<Move>,
+        ply: i32,
+    ) -> Vec<Move> {
+        let killers = self.killers(ply);
+        let color = board.active_color();
+        moves.sort_by_cached_key(|step| {
+            let score = if Some(*step) == table_move {
+                TABLE_MOVE
+            } else if board.is_capture(step) {
+                let exchange = see(board, step);
+                let base = if exchange >= 0 {
+                    GOOD_CAPTURE
+                } else {
+                    BAD_CAPTURE
+                };
+                base + mvv_lva(board, step)
+            } else if let Some(promotion) = step.promotion {
+                PROMOTION + piece_value(promotion)
+            } else if let Some(index) = killers.iter().position(|killer| *killer == Some(*step)) {
+                KILLER - index as Score
+            } else {
+                self.history[history_index(color, step)]
+            };
+            -score
+        });
+        moves
+    }
+
+    // Quiet move refuted the opponent's previous one
+    pub fn record_cutoff(&mut self, board: &Board, step: &Move, depth: u32, ply: i32) {
+        if board.is_capture(step) || step.promotion.is_some() {
+            return;
+        }
+        if let Some(killers) = self.killers.get_mut(ply as usize) {
+            if killers[0] != Some(*step) {
+                killers[1] = killers[0];
+                killers[0] = Some(*step);
+            }
+        }
+        let index = history_index(board.active_color(), step);
+        let bonus = (depth * depth) as Score;
+        self.history[index] = (self.history[index] + bonus).min(MAX_HISTORY);
+    }
+
+    fn killers(&self, ply: i32) -> [Option<Move>; 2] {
+        self.killers.get(ply as usize).copied().unwrap_or([None; 2])
+    }
+}
+
+fn square_index(position: &Position) -> usize {
+    position.vert as usize * MAX_SIZE as usize + position.hor as usize
+}
+
+fn history_index(color: Color, step: &Move) -> usize {
+    let side = match color {
+        Color::White => 0,
+        Color::Black => 1,
+    };
+    (side * SQUARES + square_index(&step.from)) * SQUARES + square_index(&step.to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(steps: &[&str]) -> Vec<Move> {
+        steps.iter().map(|step| step.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn captures_are_ordered_by_exchange_and_victim() {
+        let board = Board::from_fen("4k3/8/8/1p5p/2nr2p1/1P6/8/3QK3 w - - 0 1").unwrap();
+        let board = board.borrow();
+        let ordering = MoveOrdering::new();
+        let ordered = ordering.order(
+            &board,
+            moves(&["d1-e2", "d1-g4", "b3-c4", "d1-d4", "d1-d3"]),
+            Some("d1-d3".parse().unwrap()),
+            0,
+        );
+        // Table move, free rook, defended knight taken by a pawn, quiet move
+        // and the queen taking a defended pawn
+        let expected = moves(&["d1-d3", "d1-d4", "b3-c4", "d1-e2", "d1-g4"]);
+        assert_eq!(ordered, expected);
+    }
+
+    #[test]
+    fn quiet_cutoffs_become_killers_and_history() {
+        let board = Board::new();
+        let board = board.borrow();
+        let mut ordering = MoveOrdering::new();
+        let all = board.legal_moves(Color::White);
+        ordering.record_cutoff(&board, &"g1-f3".parse().unwrap(), 3, 2);
+        ordering.record_cutoff(&board, &"b1-c3".parse().unwrap(), 3, 2);
+        let ordered = ordering.order(&board, all.clone(), None, 2);
+        assert_eq!(ordered[..2], moves(&["b1-c3", "g1-f3"])[..]);
+
+        // At the other ply only history is left
+        ordering.record_cutoff(&board, &"b1-c3".parse().unwrap(), 2, 5);
+        let ordered = ordering.order(&board, all, None, 3);
+        assert_eq!(ordered[..2], moves(&["b1-c3", "g1-f3"])[..]);
+
+        ordering.new_search();
+        assert_eq!(ordering.killers(2), [None; 2]);
+        let index = history_index(Color::White, &"b1-c3".parse().unwrap());
+        assert_eq!(ordering.history[index], 6);
+    }
+}
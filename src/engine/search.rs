@@ -0,0 +1,453 @@
+use crate::board::Board;
+use crate::common::GameOutcome;
+use crate::engine::exchange::{mvv_lva, see};
+use crate::engine::ordering::MoveOrdering;
+use crate::engine::transposition::{Bound, TranspositionTable};
+use crate::engine::{evaluate, zobrist, Score, INFINITY, MATE};
+use crate::moves::Move;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// Deadline and stop flag are checked once per this many nodes, it has to be a power of two
+const CHECK_INTERVAL: u64 = 64;
+
+// Search goes deeper until one of the limits is reached
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub time: Option<Duration>,
+}
+
+// Result of the last completed iteration
+pub struct SearchResult {
+    // None only if there are no legal moves at all
+    pub best_move: Option<Move>,
+    pub score: Score,
+    pub depth: u32,
+    pub nodes: u64,
+    pub elapsed: Duration,
+    // Principal variation, the line both sides are expected to play
+    pub pv: Vec<Move>,
+}
+
+impl SearchResult {
+    pub fn nps(&self) -> u64 {
+        let micros = self.elapsed.as_micros().max(1) as u64;
+        self.nodes * 1_000_000 / micros
+    }
+}
+
+// Computer opponent, searches the move tree with alpha-beta pruning and iterative deepening
+pub struct Engine {
+    nodes: u64,
+    deadline: Option<Instant>,
+    stop: Arc<AtomicBool>,
+    is_stopped: bool,
+    // Kept between searches, positions of the previous move are likely to come up again
+    table: TranspositionTable,
+    ordering: MoveOrdering,
+}
+
+impl Engine {
+    pub fn new(hash_megabytes: usize) -> Self {
+        Engine {
+            nodes: 0,
+            deadline: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            is_stopped: false,
+            table: TranspositionTable::new(hash_megabytes),
+            ordering: MoveOrdering::new(),
+        }
+    }
+
+    // Setting the flag from another thread stops the current search,
+    // the move from the last completed depth is used then
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    // Reports every completed iteration, the last one is returned
+    pub fn search(
+        &mut self,
+        board: &Board,
+        limits: SearchLimits,
+        mut on_iteration: impl FnMut(&SearchResult),
+    ) -> SearchResult {
+        let start = Instant::now();
+        self.nodes = 0;
+        self.deadline = limits.time.map(|time| start + time);
+        self.stop.store(false, Ordering::Relaxed);
+        self.is_stopped = false;
+        self.table.new_search();
+        self.ordering.new_search();
+        let legal_moves = board.legal_moves(board.active_color());
+        let root_moves = self.ordering.order(board, legal_moves, None, 0);
+        let mut result = SearchResult {
+            best_move: root_moves.first().copied(),
+            score: if root_moves.is_empty() {
+                terminal_score(board, board.game_outcome_with(false), 0)
+            } else {
+                0
+            },
+            depth: 0,
+            nodes: 0,
+            elapsed: Duration::default(),
+            pv: Vec::new(),
+        };
+        if root_moves.is_empty() {
+            return result;
+        }
+
+        let max_depth = limits.depth.unwrap_or(u32::MAX);
+        let mut depth = 1;
+        while depth <= max_depth && !self.should_stop() {
+            let mut pv = Vec::new();
+            let score = self.search_root(board, &root_moves, &result.pv, depth, &mut pv);
+            if self.is_stopped {
+                break;
+            }
+            result.best_move = pv.first().copied();
+            result.score = score;
+            result.depth = depth;
+            result.nodes = self.nodes;
+            result.elapsed = start.elapsed();
+            result.pv = pv;
+            on_iteration(&result);
+            // There is nothing to look for after a forced mate is found
+            if score.abs() >= MATE - depth as Score {
+                break;
+            }
+            depth += 1;
+        }
+        result.nodes = self.nodes;
+        result.elapsed = start.elapsed();
+        result
+    }
+
+    // Best line of the previous iteration is searched first
+    fn search_root(
+        &mut self,
+        board: &Board,
+        moves: &[Move],
+        previous_pv: &[Move],
+        depth: u32,
+        pv: &mut Vec<Move>,
+    ) -> Score {
+        let mut moves = moves.to_vec();
+        if let Some(index) = previous_pv
+            .first()
+            .and_then(|best| moves.iter().position(|step| step == best))
+        {
+            let best = moves.remove(index);
+            moves.insert(0, best);
+        }
+        let mut alpha = -INFINITY;
+        for step in moves {
+            let mut line = Vec::new();
+            let next = board.after_move(&step);
+            let score = -self.alpha_beta(&next, depth - 1, 1, -INFINITY, -alpha, &mut line);
+            if self.is_stopped {
+                break;
+            }
+            if score > alpha {
+                alpha = score;
+                pv.clear();
+                pv.push(step);
+                pv.extend(line);
+            }
+        }
+        alpha
+    }
+
+    // Negamax form, the score is always from the point of view of the side to move
+    fn alpha_beta(
+        &mut self,
+        board: &Board,
+        depth: u32,
+        ply: i32,
+        mut alpha: Score,
+        beta: Score,
+        pv: &mut Vec<Move>,
+    ) -> Score {
+        self.nodes += 1;
+        if self.nodes & (CHECK_INTERVAL - 1) == 0 && self.should_stop() {
+            self.is_stopped = true;
+        }
+        if self.is_stopped {
+            return 0;
+        }
+        // Quiescence generates only the moves it needs
+        if depth == 0 {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+        let legal_moves = board.legal_moves(board.active_color());
+        if let Some(outcome) = board.game_outcome_with(!legal_moves.is_empty()) {
+            return terminal_score(board, Some(outcome), ply);
+        }
+
+        let key = zobrist::hash(board);
+        let mut table_move = None;
+        if let Some(entry) = self.table.probe(key) {
+            table_move = entry.best_move;
+            let score = entry.score(ply);
+            let is_cutoff = entry.depth >= depth
+                && match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => score >= beta,
+                    Bound::Upper => score <= alpha,
+                };
+            if is_cutoff {
+                if entry.bound == Bound::Exact {
+                    *pv = self.table_line(board, depth);
+                }
+                return score;
+            }
+        }
+
+        let moves = self.ordering.order(board, legal_moves, table_move, ply);
+        let mut best_move = None;
+        let mut bound = Bound::Upper;
+        for step in moves {
+            let mut line = Vec::new();
+            let next = board.after_move(&step);
+            let score = -self.alpha_beta(&next, depth - 1, ply + 1, -beta, -alpha, &mut line);
+            if self.is_stopped {
+                return 0;
+            }
+            if score >= beta {
+                self.ordering.record_cutoff(board, &step, depth, ply);
+                self.table
+                    .store(key, depth, Bound::Lower, beta, ply, Some(step));
+                return beta;
+            }
+            if score > alpha {
+                alpha = score;
+                best_move = Some(step);
+                bound = Bound::Exact;
+                pv.clear();
+                pv.push(step);
+                pv.extend(line);
+            }
+        }
+        self.table.store(key, depth, bound, alpha, ply, best_move);
+        alpha
+    }
+
+    // Only captures and promotions are searched past the horizon, so the position isn't judged
+    // in the middle of an exchange. Side to move may stand pat instead, unless it is in check
+    fn quiescence(&mut self, board: &Board, ply: i32, mut alpha: Score, beta: Score) -> Score {
+        self.nodes += 1;
+        if self.nodes & (CHECK_INTERVAL - 1) == 0 && self.should_stop() {
+            self.is_stopped = true;
+        }
+        if self.is_stopped {
+            return 0;
+        }
+
+        // Every evasion is tried in check, standing pat there could hide a mate
+        let color = board.active_color();
+        let is_in_check = board.is_in_check(color);
+        let mut moves = if is_in_check {
+            board.legal_moves(color)
+        } else {
+            board.legal_captures(color)
+        };
+        let has_legal_moves = !moves.is_empty() || (!is_in_check && board.has_legal_moves(color));
+        if let Some(outcome) = board.game_outcome_with(has_legal_moves) {
+            return terminal_score(board, Some(outcome), ply);
+        }
+
+        if is_in_check {
+            moves = self.ordering.order(board, moves, None, ply);
+        } else {
+            let stand_pat = evaluate(board);
+            if stand_pat >= beta {
+                return beta;
+            }
+            alpha = alpha.max(stand_pat);
+            // Captures losing material by SEE can't raise the score above standing pat
+            moves.retain(|step| step.promotion.is_some() || see(board, step) >= 0);
+            moves.sort_by_cached_key(|step| -mvv_lva(board, step));
+        }
+        for step in moves {
+            let next = board.after_move(&step);
+            let score = -self.quiescence(&next, ply + 1, -beta, -alpha);
+            if self.is_stopped {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+
+    // Principal variation restored from the table, when the search was cut short by it
+    fn table_line(&self, board: &Board, depth: u32) -> Vec<Move> {
+        let mut line = Vec::new();
+        let mut position = board.clone();
+        while (line.len() as u32) < depth {
+            let step = match self.table.probe(zobrist::hash(&position)) {
+                Some(entry) if entry.bound == Bound::Exact => entry.best_move,
+                _ => None,
+            };
+            match step.filter(|step| position.legal_moves(position.active_color()).contains(step)) {
+                Some(step) => {
+                    position = position.after_move(&step);
+                    line.push(step);
+                }
+                None => break,
+            }
+        }
+        line
+    }
+
+    fn should_stop(&mut self) -> bool {
+        self.is_stopped = self.is_stopped
+            || self.stop.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .map(|deadline| Instant::now() >= deadline)
+                .unwrap_or(false);
+        self.is_stopped
+    }
+}
+
+fn terminal_score(board: &Board, outcome: Option<GameOutcome>, ply: i32) -> Score {
+    match outcome {
+        Some(GameOutcome::Win(color, _)) if color == board.active_color() => MATE - ply,
+        Some(GameOutcome::Win(_, _)) => -(MATE - ply),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants;
+
+    fn search(fen: &str, depth: u32) -> SearchResult {
+        let board = Board::from_fen(fen).unwrap();
+        let limits = SearchLimits {
+            depth: Some(depth),
+            time: None,
+        };
+        let result = Engine::new(1).search(&board.borrow(), limits, |_| ());
+        result
+    }
+
+    #[test]
+    fn finds_mate_in_one() {
+        let result = search("6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1", 2);
+        assert_eq!(result.best_move, Some("a1-a8".parse().unwrap()));
+        assert_eq!(result.score, MATE - 1);
+        assert_eq!(result.depth, 1);
+    }
+
+    #[test]
+    fn takes_hanging_queen_but_not_guarded_pawn() {
+        let result = search("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", 2);
+        assert_eq!(result.best_move, Some("d2-d5".parse().unwrap()));
+        let guarded_pawn = search("4k3/2p5/3p4/8/8/8/3R4/4K3 w - - 0 1", 2);
+        assert_ne!(guarded_pawn.best_move, Some("d2-d6".parse().unwrap()));
+    }
+
+    #[test]
+    fn exchanges_are_resolved_past_the_horizon() {
+        // Pawn looks free at depth 1, quiescence sees the recapture
+        let result = search("4k3/8/3p4/4p3/8/8/7Q/4K3 w - - 0 1", 1);
+        assert_ne!(result.best_move, Some("h2-e5".parse().unwrap()));
+        assert!(result.score > 0);
+        // Rook trade at the horizon leaves white a queen up
+        let result = search("3rk3/8/8/8/8/8/8/3RK2Q w - - 0 1", 1);
+        assert!(result.score > 500);
+    }
+
+    #[test]
+    fn mated_side_has_no_move() {
+        let result = search("R5k1/5ppp/8/8/8/8/8/4K3 b - - 0 1", 3);
+        assert!(result.best_move.is_none());
+        assert_eq!(result.score, -MATE);
+    }
+
+    #[test]
+    fn variant_wins_count_as_mate() {
+        let variant = variants::create("kingofthehill", 0).unwrap();
+        let board = Board::from_variant_fen("7k/8/8/8/8/2K5/8/8 w - - 0 1", variant).unwrap();
+        let limits = SearchLimits {
+            depth: Some(2),
+            time: None,
+        };
+        let result = Engine::new(1).search(&board.borrow(), limits, |_| ());
+        assert_eq!(result.best_move, Some("c3-d4".parse().unwrap()));
+        assert_eq!(result.score, MATE - 1);
+    }
+
+    #[test]
+    fn every_iteration_is_reported_with_pv() {
+        let board = Board::new();
+        let limits = SearchLimits {
+            depth: Some(3),
+            time: None,
+        };
+        let mut depths = Vec::new();
+        let result = Engine::new(1).search(&board.borrow(), limits, |iteration| {
+            assert_eq!(iteration.pv.len() as u32, iteration.depth);
+            assert_eq!(iteration.pv.first(), iteration.best_move.as_ref());
+            depths.push(iteration.depth);
+        });
+        assert_eq!(depths, vec![1, 2, 3]);
+        assert_eq!(result.depth, 3);
+        assert!(result.nodes > 0);
+    }
+
+    #[test]
+    fn table_is_reused_by_the_next_search() {
+        let board =
+            Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")
+                .unwrap();
+        let limits = SearchLimits {
+            depth: Some(3),
+            time: None,
+        };
+        let mut engine = Engine::new(1);
+        let first = engine.search(&board.borrow(), limits, |_| ());
+        let second = engine.search(&board.borrow(), limits, |_| ());
+        assert!(second.nodes < first.nodes);
+        assert_eq!(second.score, first.score);
+        assert_eq!(second.pv.len(), 3);
+    }
+
+    #[test]
+    fn search_can_be_stopped() {
+        let board = Board::new();
+        let mut engine = Engine::new(1);
+        let stop = engine.stop_flag();
+        let stopper = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            stop.store(true, Ordering::Relaxed);
+        });
+        let mut completed = 0;
+        let result = engine.search(&board.borrow(), SearchLimits::default(), |iteration| {
+            completed = iteration.depth;
+        });
+        stopper.join().unwrap();
+        assert_eq!(result.depth, completed);
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn search_is_limited_by_time() {
+        let board = Board::new();
+        let limits = SearchLimits {
+            depth: None,
+            time: Some(Duration::from_millis(100)),
+        };
+        let start = Instant::now();
+        let result = Engine::new(1).search(&board.borrow(), limits, |_| ());
+        assert!(start.elapsed() < Duration::from_millis(2000));
+        assert!(result.best_move.is_some());
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+// Moves the rest of the game is expected to take, when nothing is known about it
+const MOVES_TO_GO: u32 = 30;
+// Kept for the communication and for the move to be made in time
+const SAFETY_MARGIN: Duration = Duration::from_millis(50);
+
+// Time to think about the next move, never more than a half of what is left on the clock
+pub fn time_for_move(remaining: Duration, increment: Duration) -> Duration {
+    let available = remaining.checked_sub(SAFETY_MARGIN).unwrap_or_default();
+    let planned = available / MOVES_TO_GO + increment * 3 / 4;
+    planned.min(available / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn increment_is_mostly_used() {
+        let time = time_for_move(Duration::from_secs(300), Duration::from_secs(2));
+        assert!(time > Duration::from_secs(11) && time < Duration::from_secs(12));
+    }
+
+    #[test]
+    fn low_clock_is_not_flagged() {
+        let time = time_for_move(Duration::from_millis(1000), Duration::from_secs(5));
+        assert!(time <= Duration::from_millis(500));
+        assert_eq!(
+            time_for_move(Duration::from_millis(10), Duration::from_secs(1)),
+            Duration::default()
+        );
+    }
+}
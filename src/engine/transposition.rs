@@ -0,0 +1,173 @@
+use crate::engine::{Score, DEFAULT_HASH_MB, MATE, MAX_HASH_MB, MAX_MATE_PLIES};
+use crate::moves::Move;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Bound {
+    Exact,
+    // Score is at least this, the search was cut off
+    Lower,
+    // Score is at most this, no move was good enough
+    Upper,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Entry {
+    key: u64,
+    pub depth: u32,
+    pub bound: Bound,
+    // Mate scores are counted from this position, not from the root
+    score: Score,
+    pub best_move: Option<Move>,
+    // Search the entry was stored by, old entries are replaced first
+    generation: u8,
+}
+
+impl Entry {
+    // Score as seen from the root, the position is at the given ply from it
+    pub fn score(&self, ply: i32) -> Score {
+        if self.score > MATE - MAX_MATE_PLIES {
+            self.score - ply
+        } else if self.score < -(MATE - MAX_MATE_PLIES) {
+            self.score + ply
+        } else {
+            self.score
+        }
+    }
+}
+
+// Fixed size table of searched positions, indexed by their hash
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    pub fn new(megabytes: usize) -> Self {
+        let bytes = megabytes
+            .min(MAX_HASH_MB)
+            .checked_mul(1024 * 1024)
+            .unwrap_or(DEFAULT_HASH_MB * 1024 * 1024);
+        let size = bytes / std::mem::size_of::<Option<Entry>>();
+        TranspositionTable {
+            entries: vec![None; size.max(1)],
+            generation: 0,
+        }
+    }
+
+    // Entries of previous searches are kept, but give way to the new ones
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    pub fn probe(&self, key: u64) -> Option<&Entry> {
+        self.entries[self.index(key)]
+            .as_ref()
+            .filter(|entry| entry.key == key)
+    }
+
+    // Deeper searches are kept unless they are left from an older search
+    pub fn store(
+        &mut self,
+        key: u64,
+        depth: u32,
+        bound: Bound,
+        score: Score,
+        ply: i32,
+        best_move: Option<Move>,
+    ) {
+        let generation = self.generation;
+        let index = self.index(key);
+        let slot = &mut self.entries[index];
+        let is_replaced = match slot {
+            None => true,
+            Some(old) => old.key == key || old.generation != generation || depth >= old.depth,
+        };
+        if !is_replaced {
+            return;
+        }
+        // Move of a shallower search is better than none
+        let best_move = best_move.or_else(|| {
+            slot.as_ref()
+                .filter(|old| old.key == key)
+                .and_then(|old| old.best_move)
+        });
+        let score = if score > MATE - MAX_MATE_PLIES {
+            score + ply
+        } else if score < -(MATE - MAX_MATE_PLIES) {
+            score - ply
+        } else {
+            score
+        };
+        *slot = Some(Entry {
+            key,
+            depth,
+            bound,
+            score,
+            best_move,
+            generation,
+        });
+    }
+
+    fn index(&self, key: u64) -> usize {
+        (key % self.entries.len() as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(text: &str) -> Option<Move> {
+        text.parse().ok()
+    }
+
+    #[test]
+    fn size_follows_megabytes() {
+        let table = TranspositionTable::new(1);
+        assert_eq!(
+            table.entries.len(),
+            1024 * 1024 / std::mem::size_of::<Option<Entry>>()
+        );
+        assert_eq!(TranspositionTable::new(0).entries.len(), 1);
+    }
+
+    #[test]
+    fn stored_entries_are_found_by_key() {
+        let mut table = TranspositionTable::new(1);
+        table.store(42, 3, Bound::Exact, 25, 2, step("e2-e4"));
+        let entry = table.probe(42).unwrap();
+        assert_eq!(
+            (entry.depth, entry.bound, entry.score(2)),
+            (3, Bound::Exact, 25)
+        );
+        assert_eq!(entry.best_move, step("e2-e4"));
+        assert!(table.probe(42 + table.entries.len() as u64).is_none());
+    }
+
+    #[test]
+    fn mate_scores_are_relative_to_the_position() {
+        let mut table = TranspositionTable::new(1);
+        // Mate in 5 plies from the root found 2 plies deep is mate in 3 from the position
+        table.store(7, 4, Bound::Exact, MATE - 5, 2, None);
+        assert_eq!(table.entries[table.index(7)].unwrap().score, MATE - 3);
+        // The same position reached 4 plies deep in another search
+        assert_eq!(table.probe(7).unwrap().score(4), MATE - 7);
+        table.store(8, 4, Bound::Upper, -(MATE - 6), 3, None);
+        assert_eq!(table.probe(8).unwrap().score(1), -(MATE - 4));
+    }
+
+    #[test]
+    fn deeper_and_newer_entries_win() {
+        let mut table = TranspositionTable::new(0);
+        table.store(1, 5, Bound::Exact, 10, 0, step("e2-e4"));
+        table.store(2, 3, Bound::Exact, 20, 0, None);
+        assert_eq!(table.probe(1).unwrap().depth, 5);
+        assert!(table.probe(2).is_none());
+        // Same position keeps its best move when stored without one
+        table.store(1, 6, Bound::Upper, 5, 0, None);
+        assert_eq!(table.probe(1).unwrap().best_move, step("e2-e4"));
+        table.new_search();
+        table.store(2, 1, Bound::Lower, 30, 0, None);
+        assert_eq!(table.probe(2).unwrap().score(0), 30);
+    }
+}
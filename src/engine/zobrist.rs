@@ -0,0 +1,112 @@
+use crate::board::Board;
+use crate::common::Color;
+use crate::pieces::PieceType;
+use crate::positions::{Position, MAX_SIZE};
+
+// Kinds of position features, each one has its own range of keys
+const PIECE: u64 = 1;
+const SIDE_TO_MOVE: u64 = 2;
+const EN_PASSANT: u64 = 3;
+const POCKET: u64 = 4;
+const CHECKS_GIVEN: u64 = 5;
+const DUCK: u64 = 6;
+const CASTLING: u64 = 7;
+
+// Pseudo-random key of the feature, the same for every run
+fn key(kind: u64, feature: u64) -> u64 {
+    // SplitMix64 finalizer
+    let mut z = ((kind << 48) | feature).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn square(position: &Position) -> u64 {
+    position.vert as u64 * u64::from(MAX_SIZE) + position.hor as u64
+}
+
+fn piece_index(piece_type: PieceType, color: Color) -> u64 {
+    piece_type as u64 * 2 + color as u64
+}
+
+// Zobrist hash, the same position reached by different move orders gets the same one
+pub fn hash(board: &Board) -> u64 {
+    let mut hash = 0;
+    for piece in board.pieces() {
+        // Promoted pieces turn back into pawns when they go to a pocket
+        let index =
+            (piece_index(piece.piece_type, piece.color) << 1 | piece.is_promoted() as u64) << 8;
+        hash ^= key(PIECE, index | square(&piece.position));
+    }
+    if board.active_color() == Color::Black {
+        hash ^= key(SIDE_TO_MOVE, 0);
+    }
+    if let Some(en_passant) = board.en_passant() {
+        hash ^= key(EN_PASSANT, square(&en_passant));
+    }
+    if let Some(duck) = board.duck() {
+        hash ^= key(DUCK, square(&duck));
+    }
+    for color in [Color::White, Color::Black].iter() {
+        // Every piece in the pocket is keyed by its number among the same ones
+        let pocket = board.pocket(*color);
+        for (i, piece_type) in pocket.iter().enumerate() {
+            let number = pocket[..i]
+                .iter()
+                .filter(|other| *other == piece_type)
+                .count() as u64;
+            hash ^= key(POCKET, piece_index(*piece_type, *color) << 8 | number);
+        }
+        for right in board.castling_rights_fen(*color) {
+            hash ^= key(CASTLING, (*color as u64) << 8 | right as u64);
+        }
+        let checks = u64::from(board.checks_given(*color));
+        if checks > 0 {
+            hash ^= key(CHECKS_GIVEN, (*color as u64) << 8 | checks);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::Move;
+
+    fn hash_after(fen: &str, moves: &[&str]) -> u64 {
+        let board = Board::from_fen(fen).unwrap();
+        let mut board = board.borrow().clone();
+        for step in moves {
+            board = board.after_move(&step.parse::<Move>().unwrap());
+        }
+        hash(&board)
+    }
+
+    #[test]
+    fn transpositions_share_hash() {
+        let initial = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let first = hash_after(initial, &["g1-f3", "g8-f6", "b1-c3"]);
+        let second = hash_after(initial, &["b1-c3", "g8-f6", "g1-f3"]);
+        assert_eq!(first, second);
+        let black_to_move = "rnbqkb1r/pppppppp/5n2/8/8/2N2N2/PPPPPPPP/R1BQKB1R b KQkq - 3 2";
+        assert_eq!(first, hash_after(black_to_move, &[]));
+        let white_to_move = "rnbqkb1r/pppppppp/5n2/8/8/2N2N2/PPPPPPPP/R1BQKB1R w KQkq - 3 2";
+        assert_ne!(first, hash_after(white_to_move, &[]));
+    }
+
+    #[test]
+    fn castling_rights_and_en_passant_count() {
+        let castling = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        let king_moved = hash_after(castling, &["e1-f1", "e8-f8", "f1-e1", "f8-e8"]);
+        assert_ne!(hash_after(castling, &[]), king_moved);
+        assert_eq!(
+            king_moved,
+            hash_after("r3k2r/8/8/8/8/8/8/R3K2R w - - 4 3", &[])
+        );
+        let en_passant = "4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1";
+        assert_ne!(
+            hash_after(en_passant, &["e2-e4"]),
+            hash_after("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1", &[])
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use crate::board::Board;
+use crate::common::{ChessError, ChessResult, Color};
+use crate::pieces::PieceType;
+use crate::positions::{Horizontal, Position};
+
+pub const NAMES: [&str; 6] = [
+    "pawn",
+    "pawnandmove",
+    "knight",
+    "rook",
+    "queen",
+    "queenandrook",
+];
+
+// Material odds, the stronger player starts without some of the pieces
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Handicap {
+    Pawn,
+    PawnAndMove,
+    Knight,
+    Rook,
+    Queen,
+    QueenAndRook,
+}
+
+impl Handicap {
+    pub fn create(name: &str) -> ChessResult<Handicap> {
+        match name {
+            "pawn" => Ok(Handicap::Pawn),
+            "pawnandmove" => Ok(Handicap::PawnAndMove),
+            "knight" => Ok(Handicap::Knight),
+            "rook" => Ok(Handicap::Rook),
+            "queen" => Ok(Handicap::Queen),
+            "queenandrook" => Ok(Handicap::QueenAndRook),
+            _ => Err(ChessError::InvalidInput(format!(
+                "Unknown handicap {}",
+                name
+            ))),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Handicap::Pawn => "pawn",
+            Handicap::PawnAndMove => "pawn and move",
+            Handicap::Knight => "knight",
+            Handicap::Rook => "rook",
+            Handicap::Queen => "queen",
+            Handicap::QueenAndRook => "queen and rook",
+        }
+    }
+
+    // Removes pieces of the giving side, castling rights go away together with the rook
+    pub fn apply(&self, board: &mut Board, color: Color) -> ChessResult<()> {
+        let removed: &[PieceType] = match self {
+            Handicap::Pawn | Handicap::PawnAndMove => &[PieceType::Pawn],
+            Handicap::Knight => &[PieceType::Knight],
+            Handicap::Rook => &[PieceType::Rook],
+            Handicap::Queen => &[PieceType::Queen],
+            Handicap::QueenAndRook => &[PieceType::Queen, PieceType::Rook],
+        };
+        for piece_type in removed {
+            let position = odds_piece(board, *piece_type, color).ok_or_else(|| {
+                ChessError::InvalidPosition(format!(
+                    "No {:?} to give {} odds",
+                    piece_type,
+                    self.description()
+                ))
+            })?;
+            board.extract_piece(&position);
+        }
+        // Weaker side gets the first move as well
+        if *self == Handicap::PawnAndMove {
+            board.set_active_color(!color);
+        }
+        Ok(())
+    }
+}
+
+// Traditionally it is the king's bishop pawn and the queen side knight or rook
+fn odds_piece(board: &Board, piece_type: PieceType, color: Color) -> Option<Position> {
+    let rank = if piece_type == PieceType::Pawn { 1 } else { 0 };
+    let vert = Board::relative_vertical(color, rank, board.height());
+    let mut candidates = board.pieces().filter(|piece| {
+        piece.color == color && piece.piece_type == piece_type && piece.position.vert == vert
+    });
+    if piece_type == PieceType::Pawn {
+        candidates
+            .find(|pawn| pawn.position.hor == Horizontal::HorF)
+            .map(|pawn| pawn.position)
+    } else {
+        candidates
+            .min_by_key(|piece| piece.position.hor)
+            .map(|piece| piece.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_odds(handicap: &str, color: Color) -> String {
+        let board = Board::new();
+        Handicap::create(handicap)
+            .unwrap()
+            .apply(&mut board.borrow_mut(), color)
+            .unwrap();
+        let fen = board.borrow().to_fen();
+        fen
+    }
+
+    #[test]
+    fn removed_rook_takes_castling_away() {
+        assert_eq!(
+            with_odds("rook", Color::White),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/1NBQKBNR w Kkq - 0 1"
+        );
+        assert_eq!(
+            with_odds("queenandrook", Color::Black),
+            "1nb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQk - 0 1"
+        );
+    }
+
+    #[test]
+    fn pawn_and_move() {
+        assert_eq!(
+            with_odds("pawn", Color::White),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPP1PP/RNBQKBNR w KQkq - 0 1"
+        );
+        assert_eq!(
+            with_odds("pawnandmove", Color::White),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPP1PP/RNBQKBNR b KQkq - 0 1"
+        );
+    }
+
+    #[test]
+    fn missing_piece_is_reported() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(Handicap::Knight
+            .apply(&mut board.borrow_mut(), Color::White)
+            .is_err());
+        assert!(Handicap::create("bishop").is_err());
+    }
+}
@@ -1,141 +1,882 @@
 #![warn(rust_2018_idioms)]
 
-use crate::common::{ChessError, ChessResult};
+use crate::board::Board;
+use crate::common::{CastlingSide, ChessError, ChessResult, Color, GameOutcome, StepResultData};
+use crate::handicap::Handicap;
+use crate::moves::Move;
 use crate::positions::Position;
-use crate::proto::chess::MoveCommand;
-use clap::{App, Arg};
-use protobuf::Message;
+use crate::proto::chess::{BughouseUpdate, GameSetup, MoveCommand, RefereeMessage};
+use crate::variants::{bughouse, Variant};
+use clap::{App, Arg, SubCommand};
+use protobuf::{Message, RepeatedField};
 use regex::Regex;
+use std::cell::RefCell;
 use std::io;
 use std::io::Write;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
 
 mod board;
 mod common;
+mod engine;
+mod handicap;
+mod moves;
+mod notation;
+mod perft;
+mod pgn;
 mod pieces;
 mod positions;
 mod proto;
+mod variants;
 
 #[tokio::main]
 async fn main() -> ChessResult<()> {
     let cli_args = get_cli_args();
 
+    if let Some(perft_args) = cli_args.subcommand_matches("perft") {
+        return run_perft(perft_args);
+    }
+
     let address = cli_args.value_of("address").unwrap_or("127.0.0.1:10001");
     let is_server = cli_args.is_present("server");
+    let vs_computer = cli_args.is_present("vs-computer");
 
     print!("{}", termion::clear::All);
 
-    let (my_color, mut connection) = if is_server {
-        (common::Color::White, act_as_server(address).await?)
+    // There is no connection at all when playing against the engine
+    let (my_color, mut connection, setup) = if vs_computer {
+        let my_color = if cli_args.is_present("black") {
+            Color::Black
+        } else {
+            Color::White
+        };
+        (my_color, None, make_game_setup(&cli_args))
+    } else if is_server {
+        let setup = make_game_setup(&cli_args);
+        // Broken custom position should be reported before anybody connects
+        if create_board(&setup)?.borrow().variant().is_team_game() {
+            return bughouse_server(address, &setup).await;
+        }
+        let mut connection = act_as_server(address).await?;
+        send_message(&mut connection, &setup).await?;
+        (common::Color::White, Some(connection), setup)
     } else {
-        (common::Color::Black, act_as_client(address).await?)
+        let mut connection = act_as_client(address).await?;
+        let setup = receive_message::<GameSetup>(&mut connection).await?;
+        (common::Color::Black, Some(connection), setup)
     };
 
-    let mut current_color = common::Color::White;
-
     print!("{}", termion::clear::All);
-    let board = board::Board::new();
+    let board = create_board(&setup)?;
+    let variant = board.borrow().variant().clone();
+    let is_hidden = variant.visible_squares(&board.borrow(), my_color).is_some();
+    match connection.as_mut() {
+        Some(connection) if variant.is_team_game() => {
+            return bughouse_game(connection, &setup).await;
+        }
+        Some(connection) if is_hidden && is_server => {
+            return referee_game(connection, &board, my_color).await;
+        }
+        Some(connection) if is_hidden => {
+            return hidden_game(connection, variant, my_color).await;
+        }
+        None if is_hidden || variant.is_team_game() || variant.has_special_goal() => {
+            return Err(ChessError::InvalidInput(format!(
+                "{} can't be played against the computer",
+                variant.name()
+            )));
+        }
+        _ => (),
+    }
+
+    let hash_megabytes = cli_args
+        .value_of("hash")
+        .and_then(|hash| hash.parse().ok())
+        .unwrap_or(engine::DEFAULT_HASH_MB);
+    let mut engine = engine::Engine::new(hash_megabytes);
+    let clock = cli_args.value_of("clock").and_then(parse_clock);
+    let (mut computer_clock, increment) = match clock {
+        Some((time, increment)) => (Some(time), increment),
+        None => (None, Duration::default()),
+    };
+    // Without a clock the computer needs some depth to stop at
+    let search_depth = cli_args
+        .value_of("depth")
+        .and_then(|depth| depth.parse().ok())
+        .or_else(|| if clock.is_none() { Some(3) } else { None });
+    let mut engine_info = "".to_string();
+    let is_thinking = Arc::new(AtomicBool::new(false));
+    if connection.is_none() {
+        tokio::spawn(interrupt_search(engine.stop_flag(), is_thinking.clone()));
+    }
 
     let mut command = "".to_string();
     let mut message = "".to_string();
-
-    let mut buf: Vec<u8> = Vec::new();
-    buf.resize(1024, 0);
+    let mut show_threats = false;
+    let mut show_evaluation = false;
+    let mut last_step: Option<StepResultData> = None;
+    let mut last_notation = "".to_string();
+    let start = board.borrow().clone();
+    let mut moves: Vec<String> = Vec::new();
+    let mut current_color = start.active_color();
 
     loop {
-        board.borrow().draw();
+        let checkers: &[Position] = last_step
+            .as_ref()
+            .map(|step_result| &step_result.checkers[..])
+            .unwrap_or(&[]);
+        let mut highlighted = if show_threats {
+            board.borrow().attacked_squares(!my_color)
+        } else {
+            Vec::new()
+        };
+        highlighted.extend_from_slice(checkers);
+        board.borrow().draw(&highlighted, None);
         print!("{}", termion::clear::AfterCursor);
         println!();
 
-        match board.borrow().is_in_check_state() {
+        let is_double_check = last_step
+            .as_ref()
+            .map(|step_result| step_result.is_double_check())
+            .unwrap_or(false);
+        let check_kind = if is_double_check {
+            "double check"
+        } else {
+            "check"
+        };
+        let checkers_list = checkers
+            .iter()
+            .map(|position| {
+                let icon = board
+                    .borrow()
+                    .cell(position)
+                    .as_ref()
+                    .map(|checker| checker.icon())
+                    .unwrap_or(' ');
+                format!("{}{}", icon, position)
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        println!("Variant: {}", board.borrow().variant().description());
+        if let Some(odds) = odds_description(&setup)? {
+            println!("Odds: {}", odds);
+        }
+        if !last_notation.is_empty() {
+            println!("Last move: {}", last_notation);
+        }
+        if !engine_info.is_empty() {
+            println!("Computer: {}", engine_info);
+        }
+        if show_evaluation {
+            println!("{}", engine::explain(&board.borrow()));
+        }
+        if let Some(step_result) = last_step.as_ref().filter(|step| !step.exploded.is_empty()) {
+            let exploded = step_result
+                .exploded
+                .iter()
+                .map(|piece| format!("{}{}", piece.icon(), piece.position))
+                .collect::<Vec<String>>()
+                .join(", ");
+            println!("Exploded: {}", exploded);
+        }
+        // Checkers come from the variant rules, so there is no check where kings aren't royal
+        let checked_color = board
+            .borrow()
+            .is_in_check_state()
+            .filter(|_| !checkers.is_empty());
+        match checked_color {
             None => println!(),
-            Some(color) if color == my_color => println!("You are in check!"),
-            Some(_) => println!("Your opponent is in check!"),
+            Some(color) if color == my_color => {
+                println!("You are in {} by {}!", check_kind, checkers_list)
+            }
+            Some(_) => println!("Your opponent is in {} by {}!", check_kind, checkers_list),
+        }
+
+        if let Some(outcome) = last_step
+            .as_ref()
+            .and_then(|step_result| step_result.outcome)
+        {
+            println!("{}", outcome);
+            break;
         }
 
         if current_color != my_color {
-            println!("Waiting for another player");
-            let count = connection.read(&mut buf).await?;
-            println!("{}", count);
-            let move_cmd = protobuf::parse_from_bytes::<MoveCommand>(&buf)?;
-            let result =
-                parse_other_player_command(&move_cmd).and_then(|(from, to)| -> ChessResult<()> {
-                    board.borrow_mut().move_piece(&from, &to, current_color)?;
-                    Ok(())
-                });
-            if let Err(err) = result {
-                message = err.to_string();
-            } else {
-                current_color = !current_color;
+            let move_cmd = match connection.as_mut() {
+                Some(connection) => {
+                    println!("Waiting for another player");
+                    receive_message::<MoveCommand>(connection).await?
+                }
+                None => {
+                    println!("Computer is thinking (Ctrl-C to make it move now)");
+                    io::stdout().flush()?;
+                    let position = board.borrow().clone();
+                    let limits = engine::SearchLimits {
+                        depth: search_depth,
+                        time: computer_clock.map(|clock| engine::time_for_move(clock, increment)),
+                    };
+                    is_thinking.store(true, Ordering::Relaxed);
+                    let result = engine.search(&position, limits, |iteration| {
+                        println!("{}", search_info(&position, iteration));
+                    });
+                    is_thinking.store(false, Ordering::Relaxed);
+                    engine_info = search_info(&position, &result);
+                    if let Some(clock) = computer_clock.as_mut() {
+                        *clock = clock.checked_sub(result.elapsed).unwrap_or_default() + increment;
+                        engine_info.push_str(&format!(", clock {}", format_clock(*clock)));
+                    }
+                    result
+                        .best_move
+                        .ok_or_else(|| ChessError::InvalidInput("No moves left".to_string()))?
+                        .to_proto()
+                }
+            };
+            let before = board.borrow().clone();
+            let result = Move::from_proto(&move_cmd)
+                .ok_or_else(|| ChessError::InvalidInput("Unreadable".to_string()))
+                .and_then(|step| board.borrow_mut().move_piece(&step, current_color));
+            match result {
+                Ok(step_result) => {
+                    last_notation = notation::san(&before, &step_result);
+                    moves.push(last_notation.clone());
+                    let fen = board.borrow().to_fen();
+                    if !move_cmd.notation.is_empty() && move_cmd.notation != last_notation {
+                        message = format!(
+                            "Out of sync: opponent played {}, here it is {}",
+                            move_cmd.notation, last_notation
+                        );
+                    } else if !move_cmd.fen.is_empty() && move_cmd.fen != fen {
+                        message = format!(
+                            "Out of sync: opponent has {}, here it is {}",
+                            move_cmd.fen, fen
+                        );
+                    }
+                    last_step = Some(step_result);
+                    current_color = !current_color;
+                }
+                Err(err) => message = err.to_string(),
             };
         } else {
-            println!("{}{}", termion::color::Fg(termion::color::Red), message);
-            print!(
-                "{}Command({}): ",
-                termion::color::Fg(termion::color::Reset),
-                command
-            );
-            command.clear();
+            command = prompt_command(&message, &command)?;
             message.clear();
 
-            io::stdout().flush()?;
-            io::stdin().read_line(&mut command)?;
-            command.retain(|c| !c.is_whitespace());
-
             if command == "quit" {
                 break;
             }
+            if command == "threats" {
+                show_threats = !show_threats;
+                continue;
+            }
+            if command == "eval" {
+                show_evaluation = !show_evaluation;
+                continue;
+            }
+            if command == "fen" {
+                message = board.borrow().to_fen();
+                continue;
+            }
 
-            let result =
-                parse_command(&command).and_then(|(from, to)| -> ChessResult<MoveCommand> {
-                    board.borrow_mut().move_piece(&from, &to, current_color)?;
-                    let mut move_cmd = MoveCommand::default();
-                    move_cmd.from.set_default().name = from.to_string();
-                    move_cmd.to.set_default().name = to.to_string();
+            let before = board.borrow().clone();
+            let result = parse_command(&before, &command, current_color).and_then(
+                |step| -> ChessResult<MoveCommand> {
+                    let step_result = board.borrow_mut().move_piece(&step, current_color)?;
+                    let mut move_cmd = step.to_proto();
+                    last_notation = notation::san(&before, &step_result);
+                    moves.push(last_notation.clone());
+                    move_cmd.notation = last_notation.clone();
+                    move_cmd.fen = board.borrow().to_fen();
+                    last_step = Some(step_result);
                     Ok(move_cmd)
-                });
+                },
+            );
             match result {
                 Ok(cmd) => {
-                    cmd.write_to_vec(&mut buf)?;
-                    connection
-                        .write_all(&buf[0..(cmd.get_cached_size() as usize)])
-                        .await?;
+                    if let Some(connection) = connection.as_mut() {
+                        send_message(connection, &cmd).await?;
+                    }
                     current_color = !current_color;
                 }
                 Err(err) => message = err.to_string(),
             };
         }
     }
+
+    if let Some(path) = cli_args.value_of("pgn") {
+        let outcome = last_step.and_then(|step_result| step_result.outcome);
+        let tags = pgn_tags(&setup, &start, outcome)?;
+        let game = pgn::write(&tags, &start, &moves, pgn::result(outcome));
+        std::fs::write(path, game)?;
+    }
+    Ok(())
+}
+
+// Server holds the only full board and tells the client just what it is allowed to know
+async fn referee_game(
+    connection: &mut TcpStream,
+    board: &Rc<RefCell<Board>>,
+    my_color: Color,
+) -> ChessResult<()> {
+    // Black may start in odds games
+    let mut current_color = board.borrow().active_color();
+    let mut command = "".to_string();
+    let mut message = "".to_string();
+    let mut announcements: Vec<String> = Vec::new();
+    let mut outcome: Option<GameOutcome> = None;
+    let your_turn = current_color != my_color;
+    let first_message = referee_message(&board.borrow(), !my_color, &[], your_turn, None);
+    send_message(connection, &first_message).await?;
+
+    loop {
+        let view = player_view(&board.borrow(), my_color);
+        let fog = fog_squares(&board.borrow(), my_color);
+        draw_view(&view, fog.as_deref(), &announcements);
+        if let Some(outcome) = outcome {
+            println!("{}", outcome);
+            break;
+        }
+
+        let result = if current_color == my_color {
+            command = prompt_command(&message, &command)?;
+            message.clear();
+            if command == "quit" {
+                break;
+            }
+            // Typos are not attempts, so the opponent doesn't hear about them
+            let step = match parse_command(&view, &command, current_color) {
+                Ok(step) => step,
+                Err(err) => {
+                    message = err.to_string();
+                    continue;
+                }
+            };
+            board.borrow_mut().move_piece(&step, current_color)
+        } else {
+            println!("Waiting for another player");
+            let move_cmd = receive_message::<MoveCommand>(connection).await?;
+            Move::from_proto(&move_cmd)
+                .ok_or_else(|| ChessError::InvalidInput("Unreadable".to_string()))
+                .and_then(|step| board.borrow_mut().move_piece(&step, current_color))
+        };
+        match result {
+            Ok(step_result) => {
+                let board = board.borrow();
+                announcements = board.variant().announcements(&board, &step_result);
+                outcome = step_result.outcome;
+                current_color = !current_color;
+            }
+            Err(err) => {
+                if current_color == my_color {
+                    message = err.to_string();
+                }
+                announcements = vec![format!("{:?} tried an illegal move", current_color)];
+            }
+        }
+        let your_turn = current_color != my_color;
+        let update = referee_message(
+            &board.borrow(),
+            !my_color,
+            &announcements,
+            your_turn,
+            outcome,
+        );
+        send_message(connection, &update).await?;
+    }
+    Ok(())
+}
+
+// Client only knows what the referee tells it
+async fn hidden_game(
+    connection: &mut TcpStream,
+    variant: Rc<dyn Variant>,
+    my_color: Color,
+) -> ChessResult<()> {
+    let mut command = "".to_string();
+    let mut message = "".to_string();
+    loop {
+        let update = receive_message::<RefereeMessage>(connection).await?;
+        let view = Board::from_variant_fen(&update.view, variant.clone())?
+            .borrow()
+            .clone();
+        let visible: Vec<Position> = update
+            .visible
+            .iter()
+            .filter_map(|name| name.parse().ok())
+            .collect();
+        let fog = if variant.shows_fog() {
+            Some(visible.as_slice())
+        } else {
+            None
+        };
+        loop {
+            draw_view(&view, fog, &update.announcements);
+            if !update.outcome.is_empty() {
+                println!("{}", update.outcome);
+                return Ok(());
+            }
+            if !update.your_turn {
+                println!("Waiting for another player");
+                break;
+            }
+            command = prompt_command(&message, &command)?;
+            message.clear();
+            if command == "quit" {
+                return Ok(());
+            }
+            match parse_command(&view, &command, my_color) {
+                Ok(step) => {
+                    send_message(connection, &step.to_proto()).await?;
+                    break;
+                }
+                Err(err) => message = err.to_string(),
+            }
+        }
+    }
+}
+
+// Column where the second Bughouse board starts, leaving space for pockets of the first one
+const BUGHOUSE_COLUMN: u16 = 40;
+
+// Server doesn't play, it only routes moves of four players to their boards
+async fn bughouse_server(address: &str, setup: &GameSetup) -> ChessResult<()> {
+    println!(
+        "{}{}Waiting for four players at {}",
+        termion::cursor::Goto(1, 1),
+        termion::color::Fg(termion::color::Yellow),
+        address
+    );
+    let listener = TcpListener::bind(address).await?;
+    let (moves_sender, mut moves) = mpsc::unbounded_channel();
+    let mut players = Vec::new();
+    for index in 0..4 {
+        let (stream, peer) = listener.accept().await?;
+        let (board_index, color) = bughouse::seat(index);
+        println!(
+            "{}Connected {} ({:?} on board {})",
+            termion::color::Fg(termion::color::Green),
+            peer,
+            color,
+            bughouse::board_letter(board_index)
+        );
+        let (mut reader, mut writer) = stream.into_split();
+        let seat_setup = GameSetup {
+            board: board_index as u32,
+            black: color == Color::Black,
+            ..setup.clone()
+        };
+        send_message(&mut writer, &seat_setup).await?;
+        players.push(writer);
+        // Moves from all players are handled one by one in the order they come
+        let moves_sender = moves_sender.clone();
+        tokio::spawn(async move {
+            loop {
+                let received = receive_message::<MoveCommand>(&mut reader).await;
+                let is_disconnected = received.is_err();
+                if moves_sender.send((index, received)).is_err() || is_disconnected {
+                    break;
+                }
+            }
+        });
+    }
+    print!("{}", termion::clear::All);
+
+    let boards = vec![create_board(setup)?, create_board(setup)?];
+    let mut update = bughouse_update(&boards, "", None);
+    let mut mover = None;
+    loop {
+        for (index, player) in players.iter_mut().enumerate() {
+            update.own_move = mover == Some(index);
+            // Player who left is reported by its reader, the rest still get the update
+            send_message(player, &update).await.ok();
+        }
+        let views: Vec<Board> = boards.iter().map(|board| board.borrow().clone()).collect();
+        draw_bughouse(&views, &update.last_move);
+        if !update.outcome.is_empty() {
+            println!("{}", update.outcome);
+            break;
+        }
+        println!("Waiting for players");
+
+        // Rejected moves are reported only to the player who made them
+        (mover, update) = loop {
+            let (index, received) = moves
+                .recv()
+                .await
+                .ok_or_else(|| ChessError::InvalidInput("No players left".to_string()))?;
+            let (board_index, color) = bughouse::seat(index);
+            let letter = bughouse::board_letter(board_index);
+            // Leaving the game loses it, which ends the whole match
+            let move_cmd = match received {
+                Ok(move_cmd) => move_cmd,
+                Err(err) => {
+                    let outcome = format!(
+                        "Board {}: {:?} left the game ({}), {:?} wins",
+                        letter, color, err, !color
+                    );
+                    break (
+                        None,
+                        bughouse_update(&boards, &update.last_move, Some(outcome)),
+                    );
+                }
+            };
+            let board = &boards[board_index];
+            let before = board.borrow().clone();
+            let result = Move::from_proto(&move_cmd)
+                .ok_or_else(|| ChessError::InvalidInput("Unreadable".to_string()))
+                .and_then(|step| {
+                    if before.active_color() != color {
+                        return Err(ChessError::InvalidInput("Not your turn".to_string()));
+                    }
+                    board.borrow_mut().move_piece(&step, color)
+                });
+            match result {
+                Ok(step_result) => {
+                    let partner_board = &mut boards[1 - board_index].borrow_mut();
+                    bughouse::pass_to_partner(partner_board, &step_result);
+                    let last_move = format!("{}: {}", letter, notation::san(&before, &step_result));
+                    let outcome = step_result
+                        .outcome
+                        .map(|outcome| format!("Board {}: {}", letter, outcome));
+                    break (Some(index), bughouse_update(&boards, &last_move, outcome));
+                }
+                Err(err) => {
+                    let mut rejection = bughouse_update(&boards, &update.last_move, None);
+                    rejection.error = err.to_string();
+                    rejection.own_move = true;
+                    send_message(&mut players[index], &rejection).await.ok();
+                }
+            }
+        };
+    }
     Ok(())
 }
 
-fn parse_other_player_command(command: &MoveCommand) -> ChessResult<(Position, Position)> {
-    let from = Position::from_proto(command.get_from())
-        .ok_or_else(|| ChessError::InvalidInput("Unreadable".to_string()))?;
-    let to = Position::from_proto(command.get_to())
-        .ok_or_else(|| ChessError::InvalidInput("Unreadable".to_string()))?;
-    Ok((from, to))
+// Client of the Bughouse server, it sees both boards but moves only on its own one
+async fn bughouse_game(connection: &mut TcpStream, setup: &GameSetup) -> ChessResult<()> {
+    let variant = variants::create(&setup.variant, setup.position)?;
+    let my_board = setup.board as usize;
+    let my_color = if setup.black {
+        Color::Black
+    } else {
+        Color::White
+    };
+    let mut command = "".to_string();
+    // Sent move isn't answered yet, updates from the other board may come before the answer
+    let mut is_awaiting_answer = false;
+    loop {
+        let update = receive_message::<BughouseUpdate>(connection).await?;
+        if update.own_move {
+            is_awaiting_answer = false;
+        }
+        let boards = update
+            .boards
+            .iter()
+            .map(|fen| {
+                Ok(Board::from_variant_fen(fen, variant.clone())?
+                    .borrow()
+                    .clone())
+            })
+            .collect::<ChessResult<Vec<Board>>>()?;
+        let board = boards
+            .get(my_board)
+            .ok_or_else(|| ChessError::InvalidInput("No board to play on".to_string()))?;
+        let is_my_turn = board.active_color() == my_color && !is_awaiting_answer;
+        let mut message = update.error.clone();
+        loop {
+            draw_bughouse(&boards, &update.last_move);
+            println!(
+                "You play {:?} on board {}",
+                my_color,
+                bughouse::board_letter(my_board)
+            );
+            if !update.outcome.is_empty() {
+                println!("{}", update.outcome);
+                return Ok(());
+            }
+            if !is_my_turn {
+                println!("Waiting for other players");
+                break;
+            }
+            command = prompt_command(&message, &command)?;
+            message.clear();
+            if command == "quit" {
+                return Ok(());
+            }
+            match parse_command(board, &command, my_color) {
+                Ok(step) => {
+                    send_message(connection, &step.to_proto()).await?;
+                    is_awaiting_answer = true;
+                    break;
+                }
+                Err(err) => message = err.to_string(),
+            }
+        }
+    }
+}
+
+fn bughouse_update(
+    boards: &[Rc<RefCell<Board>>],
+    last_move: &str,
+    outcome: Option<String>,
+) -> BughouseUpdate {
+    BughouseUpdate {
+        boards: RepeatedField::from_vec(
+            boards.iter().map(|board| board.borrow().to_fen()).collect(),
+        ),
+        last_move: last_move.to_string(),
+        outcome: outcome.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+// Boards are drawn side by side, pockets are shown next to each of them
+fn draw_bughouse(boards: &[Board], last_move: &str) {
+    for (index, board) in boards.iter().enumerate() {
+        let column = 3 + index as u16 * BUGHOUSE_COLUMN;
+        print!(
+            "{}{}Board {}",
+            termion::cursor::Goto(column, 2),
+            termion::clear::CurrentLine,
+            bughouse::board_letter(index)
+        );
+        board.draw_at(column, &[], None);
+    }
+    print!("{}", termion::clear::AfterCursor);
+    println!();
+    println!("Variant: {}", boards[0].variant().description());
+    if !last_move.is_empty() {
+        println!("Last move: {}", last_move);
+    }
+}
+
+fn player_view(board: &Board, color: Color) -> Board {
+    board
+        .variant()
+        .visible_squares(board, color)
+        .map(|visible| board.masked(&visible))
+        .unwrap_or_else(|| board.clone())
+}
+
+// Squares the player sees when the rest of the board is covered by fog
+fn fog_squares(board: &Board, color: Color) -> Option<Vec<Position>> {
+    if !board.variant().shows_fog() {
+        return None;
+    }
+    board.variant().visible_squares(board, color)
+}
+
+fn referee_message(
+    board: &Board,
+    color: Color,
+    announcements: &[String],
+    your_turn: bool,
+    outcome: Option<GameOutcome>,
+) -> RefereeMessage {
+    RefereeMessage {
+        view: player_view(board, color).to_fen(),
+        announcements: RepeatedField::from_vec(announcements.to_vec()),
+        visible: RepeatedField::from_vec(
+            fog_squares(board, color)
+                .unwrap_or_default()
+                .iter()
+                .map(|square| square.to_string())
+                .collect(),
+        ),
+        your_turn,
+        outcome: outcome
+            .map(|outcome| outcome.to_string())
+            .unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+fn draw_view(view: &Board, visible: Option<&[Position]>, announcements: &[String]) {
+    view.draw(&[], visible);
+    print!("{}", termion::clear::AfterCursor);
+    println!();
+    println!("Variant: {}", view.variant().description());
+    for announcement in announcements {
+        println!("Referee: {}", announcement);
+    }
+}
+
+// Shows the message in red and reads the next command with whitespaces removed
+fn prompt_command(message: &str, previous: &str) -> ChessResult<String> {
+    println!("{}{}", termion::color::Fg(termion::color::Red), message);
+    print!(
+        "{}Command({}): ",
+        termion::color::Fg(termion::color::Reset),
+        previous
+    );
+    io::stdout().flush()?;
+    let mut command = String::new();
+    io::stdin().read_line(&mut command)?;
+    command.retain(|c| !c.is_whitespace());
+    Ok(command)
+}
+
+// Castling is accepted as O-O and O-O-O as well, it is the only way for some Chess960 cases
+fn parse_command(board: &Board, command: &str, color: Color) -> ChessResult<Move> {
+    if let Some((step, duck)) = command.split_once(',') {
+        let mut step = parse_command(board, step, color)?;
+        step.duck = Some(duck.parse()?);
+        return Ok(step);
+    }
+    let castling_side = match command.to_ascii_uppercase().replace('0', "O").as_str() {
+        "O-O" => Some(CastlingSide::King),
+        "O-O-O" => Some(CastlingSide::Queen),
+        _ => None,
+    };
+    match castling_side {
+        Some(side) => board
+            .castling_move(color, side)
+            .ok_or_else(|| ChessError::InvalidInput(command.to_string())),
+        None => command.parse(),
+    }
+}
+
+fn make_game_setup(args: &clap::ArgMatches<'_>) -> GameSetup {
+    let variant = args.value_of("variant").unwrap_or("standard");
+    let position = if variant == "chess960" {
+        args.value_of("position")
+            .and_then(|position| position.parse().ok())
+            .unwrap_or_else(variants::chess960::random_index)
+    } else {
+        0
+    };
+    GameSetup {
+        variant: variant.to_string(),
+        position: position.into(),
+        fen: args.value_of("fen").unwrap_or_default().to_string(),
+        handicap: args.value_of("handicap").unwrap_or_default().to_string(),
+        handicap_black: args.value_of("handicap-side") == Some("black"),
+        ..Default::default()
+    }
+}
+
+// Odds are applied on top of the starting position, so both sides end up with the same board
+fn create_board(setup: &GameSetup) -> ChessResult<Rc<RefCell<Board>>> {
+    let variant = variants::create(&setup.variant, setup.position)?;
+    let board = if setup.fen.is_empty() {
+        Board::new_variant(variant)?
+    } else {
+        Board::from_variant_fen(&setup.fen, variant)?
+    };
+    if !setup.handicap.is_empty() {
+        let handicap = Handicap::create(&setup.handicap)?;
+        handicap.apply(&mut board.borrow_mut(), handicap_color(setup))?;
+    }
+    Ok(board)
+}
+
+fn handicap_color(setup: &GameSetup) -> Color {
+    if setup.handicap_black {
+        Color::Black
+    } else {
+        Color::White
+    }
+}
+
+fn odds_description(setup: &GameSetup) -> ChessResult<Option<String>> {
+    if setup.handicap.is_empty() {
+        return Ok(None);
+    }
+    let handicap = Handicap::create(&setup.handicap)?;
+    Ok(Some(format!(
+        "{:?} gives {} odds",
+        handicap_color(setup),
+        handicap.description()
+    )))
 }
 
-fn parse_command(command: &str) -> ChessResult<(Position, Position)> {
-    if command.len() != 5 {
-        return Err(ChessError::InvalidInput(command.to_string()));
+// Starting position is recorded whenever it isn't the variant's own one, odds included
+fn pgn_tags(
+    setup: &GameSetup,
+    start: &Board,
+    outcome: Option<GameOutcome>,
+) -> ChessResult<Vec<(&'static str, String)>> {
+    let mut tags = vec![
+        ("Event", "rust-chess game".to_string()),
+        ("Site", "?".to_string()),
+        ("Date", "????.??.??".to_string()),
+        ("Round", "-".to_string()),
+        ("White", "?".to_string()),
+        ("Black", "?".to_string()),
+        ("Result", pgn::result(outcome).to_string()),
+    ];
+    let variant = start.variant();
+    if variant.name() != "standard" {
+        tags.push(("Variant", variant.name().to_string()));
+    }
+    if let Some(odds) = odds_description(setup)? {
+        tags.push(("Handicap", odds));
     }
-    let middle = command.bytes().nth(2);
-    if middle.is_none() || (middle.unwrap() != b' ' && middle.unwrap() != b'-') {
-        return Err(ChessError::InvalidInput(command.to_string()));
+    let fen = start.to_fen();
+    if fen != Board::new_variant(variant.clone())?.borrow().to_fen() {
+        tags.push(("SetUp", "1".to_string()));
+        tags.push(("FEN", fen));
     }
+    Ok(tags)
+}
 
-    let from: Position = command.chars().take(2).collect::<String>().parse()?;
-    let to: Position = command
-        .chars()
-        .skip(3)
-        .take(2)
-        .collect::<String>()
-        .parse()?;
+// Largest message accepted from the other side, real ones are a few hundred bytes at most
+const MAX_MESSAGE_SIZE: u32 = 64 * 1024;
 
-    Ok((from, to))
+// Messages are prefixed with their size, so they can't be glued together or split
+async fn send_message<M: Message>(
+    connection: &mut (impl AsyncWrite + Unpin),
+    message: &M,
+) -> ChessResult<()> {
+    let bytes = message.write_to_bytes()?;
+    connection.write_u32(bytes.len() as u32).await?;
+    connection.write_all(&bytes).await?;
+    Ok(())
+}
+
+async fn receive_message<M: Message>(connection: &mut (impl AsyncRead + Unpin)) -> ChessResult<M> {
+    let size = connection.read_u32().await?;
+    if size > MAX_MESSAGE_SIZE {
+        return Err(ChessError::InvalidInput(format!(
+            "Message too long ({} bytes)",
+            size
+        )));
+    }
+    let mut bytes = vec![0; size as usize];
+    connection.read_exact(&mut bytes).await?;
+    Ok(M::parse_from_bytes(&bytes)?)
+}
+
+fn run_perft(args: &clap::ArgMatches<'_>) -> ChessResult<()> {
+    let depth: u32 = args.value_of("depth").unwrap_or("1").parse().unwrap_or(1);
+    let variant = match args.value_of("variant") {
+        Some(name) => Some(variants::create(name, 518)?),
+        None => None,
+    };
+    let board = match (args.value_of("fen"), variant) {
+        (Some(fen), Some(variant)) => Board::from_variant_fen(fen, variant)?,
+        (Some(fen), None) => Board::from_fen(fen)?,
+        (None, Some(variant)) => Board::new_variant(variant)?,
+        (None, None) => Board::new(),
+    };
+    let board = board.borrow();
+    if args.is_present("divide") {
+        let divided = board.divide(depth);
+        for (step, nodes) in &divided {
+            println!("{}: {}", step, nodes);
+        }
+        println!();
+        println!("Moves: {}", divided.len());
+        println!(
+            "Nodes: {}",
+            divided.iter().map(|(_, nodes)| nodes).sum::<u64>()
+        );
+    } else {
+        println!("Nodes: {}", board.perft(depth));
+    }
+    Ok(())
 }
 
 fn is_valid_address(v: String) -> Result<(), String> {
@@ -149,6 +890,89 @@ fn is_valid_address(v: String) -> Result<(), String> {
     }
 }
 
+fn is_valid_chess960_position(v: String) -> Result<(), String> {
+    match v.parse::<u16>() {
+        Ok(position) if position < variants::chess960::POSITIONS_COUNT => Ok(()),
+        _ => Err(String::from(
+            "Chess960 position should be a number from 0 to 959",
+        )),
+    }
+}
+
+// Ctrl-C stops the computer's search, it quits the game as usual otherwise
+async fn interrupt_search(stop: Arc<AtomicBool>, is_thinking: Arc<AtomicBool>) {
+    while tokio::signal::ctrl_c().await.is_ok() {
+        if is_thinking.load(Ordering::Relaxed) {
+            stop.store(true, Ordering::Relaxed);
+        } else {
+            println!("{}", termion::color::Fg(termion::color::Reset));
+            std::process::exit(130);
+        }
+    }
+}
+
+// Depth, score, speed and the expected line in SAN
+fn search_info(board: &Board, result: &engine::SearchResult) -> String {
+    let mut position = board.clone();
+    let mut line = Vec::new();
+    for step in result.pv.iter() {
+        let before = position.clone();
+        match position.move_piece(step, before.active_color()) {
+            Ok(step_result) => line.push(notation::san(&before, &step_result)),
+            Err(_) => break,
+        }
+    }
+    format!(
+        "depth {}, score {}, {} nodes, {} nps, pv {}",
+        result.depth,
+        engine::format_score(result.score),
+        result.nodes,
+        result.nps(),
+        line.join(" ")
+    )
+}
+
+fn format_clock(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+// Minutes for the game and seconds added after each move, like 5+3
+fn parse_clock(clock: &str) -> Option<(Duration, Duration)> {
+    let (minutes, increment) = clock.split_once('+').unwrap_or((clock, "0"));
+    let minutes: f64 = minutes
+        .parse()
+        .ok()
+        .filter(|minutes: &f64| *minutes > 0.0)?;
+    let increment: u64 = increment.parse().ok()?;
+    Some((
+        Duration::from_secs_f64(minutes * 60.0),
+        Duration::from_secs(increment),
+    ))
+}
+
+fn is_valid_clock(v: String) -> Result<(), String> {
+    parse_clock(&v)
+        .map(|_| ())
+        .ok_or_else(|| String::from("Clock should be given as minutes+increment, like 5+3"))
+}
+
+fn is_valid_hash(v: String) -> Result<(), String> {
+    match v.parse::<usize>() {
+        Ok(1..=engine::MAX_HASH_MB) => Ok(()),
+        _ => Err(format!(
+            "Hash size should be from 1 to {} MB",
+            engine::MAX_HASH_MB
+        )),
+    }
+}
+
+fn is_valid_depth(v: String) -> Result<(), String> {
+    v.parse::<u32>()
+        .map(|_| ())
+        .map_err(|_| String::from("Depth should be a non-negative number"))
+}
+
 fn get_cli_args() -> clap::ArgMatches<'static> {
     App::new("rust-chess")
         .version("1.0")
@@ -168,6 +992,113 @@ fn get_cli_args() -> clap::ArgMatches<'static> {
                 .validator(is_valid_address)
                 .help("If acts as a server - address:port to bind to. If acts as a client - address:port of the server."),
         )
+        .arg(
+            Arg::with_name("variant")
+                .long("variant")
+                .takes_value(true)
+                .possible_values(&variants::NAMES)
+                .help("Rules to play by, chosen by the server (standard by default)"),
+        )
+        .arg(
+            Arg::with_name("position")
+                .long("position")
+                .takes_value(true)
+                .validator(is_valid_chess960_position)
+                .help("Chess960 starting position number, random if not set"),
+        )
+        .arg(
+            Arg::with_name("fen")
+                .long("fen")
+                .takes_value(true)
+                .help("Custom starting position, fairy pieces (A, C, M, L, G) are allowed"),
+        )
+        .arg(
+            Arg::with_name("handicap")
+                .long("handicap")
+                .takes_value(true)
+                .possible_values(&handicap::NAMES)
+                .help("Material odds given by one of the sides, chosen by the server"),
+        )
+        .arg(
+            Arg::with_name("handicap-side")
+                .long("handicap-side")
+                .takes_value(true)
+                .requires("handicap")
+                .possible_values(&["white", "black"])
+                .help("Side giving the odds (white by default)"),
+        )
+        .arg(
+            Arg::with_name("vs-computer")
+                .long("vs-computer")
+                .conflicts_with_all(&["server", "address"])
+                .help("Play offline against the computer"),
+        )
+        .arg(
+            Arg::with_name("black")
+                .long("black")
+                .requires("vs-computer")
+                .help("Play black against the computer"),
+        )
+        .arg(
+            Arg::with_name("depth")
+                .long("depth")
+                .takes_value(true)
+                .requires("vs-computer")
+                .validator(is_valid_depth)
+                .help("How many plies the computer looks ahead (3 by default if there is no clock)"),
+        )
+        .arg(
+            Arg::with_name("hash")
+                .long("hash")
+                .takes_value(true)
+                .requires("vs-computer")
+                .validator(is_valid_hash)
+                .help("Memory for the computer's transposition table in MB (16 by default)"),
+        )
+        .arg(
+            Arg::with_name("clock")
+                .long("clock")
+                .takes_value(true)
+                .requires("vs-computer")
+                .validator(is_valid_clock)
+                .help("Computer's clock as minutes+increment in seconds, like 5+3"),
+        )
+        .arg(
+            Arg::with_name("pgn")
+                .long("pgn")
+                .takes_value(true)
+                .help("File to save the game to in PGN when it is over"),
+        )
+        .subcommand(
+            SubCommand::with_name("perft")
+                .about("Counts legal move paths to verify move generation")
+                .arg(
+                    Arg::with_name("depth")
+                        .required(true)
+                        .validator(is_valid_depth)
+                        .help("Number of plies to look ahead"),
+                )
+                .arg(
+                    Arg::with_name("fen")
+                        .short("f")
+                        .long("fen")
+                        .takes_value(true)
+                        .help("Position to start from, initial position if not set"),
+                )
+                .arg(
+                    Arg::with_name("variant")
+                        .long("variant")
+                        .takes_value(true)
+                        .possible_values(&variants::NAMES)
+                        .help("Rules to count moves by (standard by default)"),
+                )
+                .arg(
+                    Arg::with_name("divide")
+                        .short("d")
+                        .long("divide")
+                        .help("Print node counts for each first move"),
+                ),
+        )
         .get_matches()
 }
 
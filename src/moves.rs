@@ -0,0 +1,158 @@
+use crate::common::ChessError;
+use crate::common::ChessError::InvalidInput;
+use crate::pieces::PieceType;
+use crate::positions::Position;
+use crate::proto::chess::MoveCommand;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Move {
+    pub from: Position,
+    pub to: Position,
+    pub promotion: Option<PieceType>,
+    // Piece put from the pocket, from is the same as to for such moves
+    pub drop: Option<PieceType>,
+    // Square the duck is put on after the move in Duck chess
+    pub duck: Option<Position>,
+}
+
+impl Move {
+    pub fn new(from: Position, to: Position) -> Move {
+        Move {
+            from,
+            to,
+            promotion: None,
+            drop: None,
+            duck: None,
+        }
+    }
+
+    pub fn with_promotion(from: Position, to: Position, promotion: PieceType) -> Move {
+        Move {
+            from,
+            to,
+            promotion: Some(promotion),
+            drop: None,
+            duck: None,
+        }
+    }
+
+    pub fn with_drop(piece_type: PieceType, to: Position) -> Move {
+        Move {
+            from: to,
+            to,
+            promotion: None,
+            drop: Some(piece_type),
+            duck: None,
+        }
+    }
+
+    pub fn from_proto(command: &MoveCommand) -> Option<Move> {
+        let from = Position::from_proto(command.get_from())?;
+        let to = Position::from_proto(command.get_to())?;
+        let promotion = match command.promotion.chars().next() {
+            None => None,
+            Some(letter) => Some(PieceType::from_letter(letter)?),
+        };
+        let drop = match command.drop.chars().next() {
+            None => None,
+            Some(letter) => Some(PieceType::from_letter(letter)?),
+        };
+        let duck = match command.duck.as_ref() {
+            None => None,
+            Some(cell) => Some(Position::from_proto(cell)?),
+        };
+        Some(Move {
+            from,
+            to,
+            promotion,
+            drop,
+            duck,
+        })
+    }
+
+    pub fn to_proto(self) -> MoveCommand {
+        let mut command = MoveCommand::default();
+        command.from.set_default().name = self.from.to_string();
+        command.to.set_default().name = self.to.to_string();
+        if let Some(promotion) = self.promotion {
+            command.promotion = promotion.letter().to_string();
+        }
+        if let Some(drop) = self.drop {
+            command.drop = drop.letter().to_string();
+        }
+        if let Some(duck) = self.duck {
+            command.duck.set_default().name = duck.to_string();
+        }
+        command
+    }
+}
+
+// Accepts "e2-e4", "e2e4", promotions like "e7-e8q" or "e7-e8=Q" and drops like "N@f3",
+// Duck chess moves have the duck square after a comma, like "e2-e4,d5"
+impl std::str::FromStr for Move {
+    type Err = ChessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidInput(s.to_string());
+        if !s.is_ascii() {
+            return Err(invalid());
+        }
+        if let Some((step, duck)) = s.split_once(',') {
+            let mut step: Move = step.parse()?;
+            step.duck = Some(duck.parse()?);
+            return Ok(step);
+        }
+        if let Some(at) = s.find('@') {
+            let piece_type = match &s[..at] {
+                "" => PieceType::Pawn,
+                letter if letter.len() == 1 => {
+                    PieceType::from_letter(letter.chars().next().unwrap()).ok_or_else(invalid)?
+                }
+                _ => return Err(invalid()),
+            };
+            return Ok(Move::with_drop(piece_type, s[at + 1..].parse()?));
+        }
+        let (from, rest) = split_square(s);
+        let from: Position = from.parse()?;
+        let rest = rest.strip_prefix('-').unwrap_or(rest);
+        let (to, suffix) = split_square(rest);
+        let to: Position = to.parse()?;
+        let suffix = suffix.strip_prefix('=').unwrap_or(suffix);
+        let mut letters = suffix.chars();
+        let promotion = match (letters.next(), letters.next()) {
+            (None, _) => None,
+            (Some(letter), None) => Some(PieceType::from_letter(letter).ok_or_else(invalid)?),
+            _ => return Err(invalid()),
+        };
+        Ok(Move {
+            from,
+            to,
+            promotion,
+            drop: None,
+            duck: None,
+        })
+    }
+}
+
+// Square name is a file letter followed by the rank number, which may have two digits
+fn split_square(s: &str) -> (&str, &str) {
+    let length = 1 + s.bytes().skip(1).take_while(|x| x.is_ascii_digit()).count();
+    s.split_at(length.min(s.len()))
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(drop) = self.drop {
+            write!(f, "{}@{}", drop.letter(), self.to)?;
+        } else {
+            write!(f, "{}-{}", self.from, self.to)?;
+        }
+        if let Some(promotion) = self.promotion {
+            write!(f, "{}", promotion.letter())?;
+        }
+        if let Some(duck) = self.duck {
+            write!(f, ",{}", duck)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use crate::board::Board;
+use crate::common::{CastlingSide, StepResultData};
+use crate::pieces::PieceType;
+use crate::positions::Position;
+
+// Standard algebraic notation of the move, board should be the one before the move
+pub fn san(board: &Board, result: &StepResultData) -> String {
+    let step = &result.step;
+    let mut notation = match result.castling {
+        None if step.drop.is_some() => {
+            format!("{}@{}", result.piece_type.letter(), square(&step.to))
+        }
+        Some(CastlingSide::King) => "O-O".to_string(),
+        Some(CastlingSide::Queen) => "O-O-O".to_string(),
+        None if result.piece_type == PieceType::Pawn => {
+            let mut notation = String::new();
+            if result.eaten.is_some() {
+                notation.push(file(&step.from));
+                notation.push('x');
+            }
+            notation.push_str(&square(&step.to));
+            if let Some(promotion) = result.promotion {
+                notation.push('=');
+                notation.push(promotion.letter());
+            }
+            notation
+        }
+        None => {
+            let mut notation = result.piece_type.letter().to_string();
+            notation.push_str(&disambiguation(board, result));
+            if result.eaten.is_some() {
+                notation.push('x');
+            }
+            notation.push_str(&square(&step.to));
+            notation
+        }
+    };
+    if let Some(duck) = step.duck {
+        notation.push(',');
+        notation.push_str(&square(&duck));
+    }
+    if result.is_mate() {
+        notation.push('#');
+    } else if result.is_check() {
+        notation.push('+');
+    }
+    notation
+}
+
+fn disambiguation(board: &Board, result: &StepResultData) -> String {
+    let step = &result.step;
+    let rivals: Vec<Position> = board
+        .legal_moves(result.color)
+        .iter()
+        .filter(|other| other.to == step.to && other.from != step.from)
+        .filter(|other| {
+            board
+                .cell(&other.from)
+                .as_ref()
+                .map(|piece| piece.piece_type == result.piece_type)
+                .unwrap_or(false)
+        })
+        .map(|other| other.from)
+        .collect();
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals.iter().all(|other| other.hor != step.from.hor) {
+        file(&step.from).to_string()
+    } else if rivals.iter().all(|other| other.vert != step.from.vert) {
+        rank(&step.from)
+    } else {
+        square(&step.from)
+    }
+}
+
+fn file(position: &Position) -> char {
+    (position.hor as u8 + b'a') as char
+}
+
+fn rank(position: &Position) -> String {
+    (position.vert as u8 + 1).to_string()
+}
+
+fn square(position: &Position) -> String {
+    format!("{}{}", file(position), rank(position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::Move;
+
+    fn san_after(fen: &str, step: &str) -> String {
+        let board = Board::from_fen(fen).unwrap();
+        let before = board.borrow().clone();
+        let color = before.active_color();
+        let result = board
+            .borrow_mut()
+            .move_piece(&step.parse::<Move>().unwrap(), color)
+            .unwrap();
+        san(&before, &result)
+    }
+
+    #[test]
+    fn pawn_moves() {
+        let initial = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(san_after(initial, "e2-e4"), "e4");
+        let en_passant = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1";
+        assert_eq!(san_after(en_passant, "e5-d6"), "exd6");
+        let promotion = "8/1P2k3/8/8/8/8/8/4K3 w - - 0 1";
+        assert_eq!(san_after(promotion, "b7-b8n"), "b8=N");
+        assert_eq!(san_after(promotion, "b7-b8"), "b8=Q");
+    }
+
+    #[test]
+    fn piece_moves_with_disambiguation() {
+        let knights = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+        assert_eq!(san_after(knights, "b1-d2"), "Nbd2");
+        let rooks = "4k3/R7/8/8/8/8/8/R3K3 w - - 0 1";
+        assert_eq!(san_after(rooks, "a1-a4"), "R1a4");
+        let queens = "8/8/8/7k/8/8/Q1Q5/Q3K3 w - - 0 1";
+        assert_eq!(san_after(queens, "a2-b1"), "Qa2b1");
+    }
+
+    #[test]
+    fn castling_checks_and_mate() {
+        let castling = "3k4/8/8/8/8/8/8/R3K2R w KQ - 0 1";
+        assert_eq!(san_after(castling, "e1-g1"), "O-O");
+        assert_eq!(san_after(castling, "e1-c1"), "O-O-O+");
+        let mate = "6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1";
+        assert_eq!(san_after(mate, "a1-a8"), "Ra8#");
+        let capture = "3rk3/8/8/8/8/8/8/3RK3 w - - 0 1";
+        assert_eq!(san_after(capture, "d1-d8"), "Rxd8+");
+    }
+}
@@ -0,0 +1,161 @@
+use crate::board::Board;
+use crate::moves::Move;
+
+impl Board {
+    // Counts leaf nodes of the legal moves tree, main tool to verify move generation
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves(self.active_color());
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        moves
+            .iter()
+            .map(|step| self.after_move(step).perft(depth - 1))
+            .sum()
+    }
+
+    // Same as perft, but with node counts split by the first move
+    pub fn divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        self.legal_moves(self.active_color())
+            .into_iter()
+            .map(|step| (step, self.after_move(&step).perft(depth.saturating_sub(1))))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::variants;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // Positions and node counts are from https://www.chessprogramming.org/Perft_Results
+    const INITIAL: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+    const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+    const POSITION_4_MIRRORED: &str =
+        "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+    const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+    const POSITION_6: &str =
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+    // Chess960 positions and counts are from https://www.chessprogramming.org/Chess960_Perft_Results
+    const CHESS960_1: &str = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+    const CHESS960_2: &str = "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9";
+    const CHESS960_3: &str = "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9";
+    const CHESS960_4: &str = "qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9";
+    const CHESS960_5: &str = "1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9";
+    const CHESS960_6: &str = "qnbnr1kr/ppp1b1pp/4p3/3p1p2/8/2NPP3/PPP1BPPP/QNB1R1KR w HEhe - 1 9";
+
+    fn check_perft(fen: &str, expected: &[u64]) {
+        check_board_perft(Board::from_fen(fen).unwrap(), fen, expected);
+    }
+
+    fn check_chess960_perft(fen: &str, expected: &[u64]) {
+        let variant = variants::create("chess960", 518).unwrap();
+        check_board_perft(
+            Board::from_variant_fen(fen, variant).unwrap(),
+            fen,
+            expected,
+        );
+    }
+
+    fn check_board_perft(board: Rc<RefCell<Board>>, fen: &str, expected: &[u64]) {
+        for (depth, nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                board.borrow().perft(depth as u32 + 1),
+                *nodes,
+                "{} at depth {}",
+                fen,
+                depth + 1
+            );
+        }
+    }
+
+    #[test]
+    fn initial_position() {
+        check_perft(INITIAL, &[20, 400, 8902]);
+    }
+
+    #[test]
+    fn initial_position_from_new() {
+        assert_eq!(Board::new().borrow().perft(3), 8902);
+    }
+
+    #[test]
+    fn kiwipete() {
+        check_perft(KIWIPETE, &[48, 2039]);
+    }
+
+    #[test]
+    fn position_3() {
+        check_perft(POSITION_3, &[14, 191, 2812]);
+    }
+
+    #[test]
+    fn position_4() {
+        check_perft(POSITION_4, &[6, 264, 9467]);
+    }
+
+    #[test]
+    fn position_4_mirrored() {
+        check_perft(POSITION_4_MIRRORED, &[6, 264, 9467]);
+    }
+
+    #[test]
+    fn position_5() {
+        check_perft(POSITION_5, &[44, 1486]);
+    }
+
+    #[test]
+    fn position_6() {
+        check_perft(POSITION_6, &[46, 2079]);
+    }
+
+    #[test]
+    fn chess960_positions() {
+        check_chess960_perft(CHESS960_1, &[21, 528]);
+        check_chess960_perft(CHESS960_2, &[21, 807]);
+        check_chess960_perft(CHESS960_3, &[20, 479]);
+        check_chess960_perft(CHESS960_4, &[22, 593]);
+        check_chess960_perft(CHESS960_5, &[28, 1120]);
+        check_chess960_perft(CHESS960_6, &[29, 899]);
+    }
+
+    #[test]
+    fn chess960_standard_position() {
+        // Position 518 is the standard setup
+        let board = Board::new_variant(variants::create("chess960", 518).unwrap()).unwrap();
+        assert_eq!(board.borrow().perft(3), 8902);
+    }
+
+    #[test]
+    #[ignore]
+    fn deep_positions() {
+        check_perft(INITIAL, &[20, 400, 8902, 197_281]);
+        check_perft(KIWIPETE, &[48, 2039, 97_862]);
+        check_perft(POSITION_3, &[14, 191, 2812, 43_238]);
+        check_perft(POSITION_4, &[6, 264, 9467, 422_333]);
+        check_perft(POSITION_5, &[44, 1486, 62_379]);
+        check_perft(POSITION_6, &[46, 2079, 89_890]);
+        check_chess960_perft(CHESS960_1, &[21, 528, 12_189, 326_672]);
+        check_chess960_perft(CHESS960_2, &[21, 807, 18_002, 667_366]);
+        check_chess960_perft(CHESS960_3, &[20, 479, 10_471, 273_318]);
+        check_chess960_perft(CHESS960_4, &[22, 593, 13_440, 382_958]);
+        check_chess960_perft(CHESS960_5, &[28, 1120, 31_058, 1_171_749]);
+        check_chess960_perft(CHESS960_6, &[29, 899, 26_578, 824_055]);
+    }
+
+    #[test]
+    fn divide_sums_to_perft() {
+        let board = Board::from_fen(KIWIPETE).unwrap();
+        let divided = board.borrow().divide(2);
+        assert_eq!(divided.len(), 48);
+        assert_eq!(divided.iter().map(|(_, nodes)| nodes).sum::<u64>(), 2039);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::board::Board;
+use crate::common::{Color, GameOutcome};
+
+// Movetext lines are kept under 80 characters as the export format asks
+const LINE_WIDTH: usize = 79;
+
+pub fn result(outcome: Option<GameOutcome>) -> &'static str {
+    match outcome {
+        Some(GameOutcome::Win(Color::White, _)) => "1-0",
+        Some(GameOutcome::Win(Color::Black, _)) => "0-1",
+        Some(GameOutcome::Draw(_)) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+// Tag pairs followed by the moves in SAN, starting from the given position
+pub fn write(tags: &[(&str, String)], start: &Board, moves: &[String], result: &str) -> String {
+    let mut pgn = String::new();
+    for (name, value) in tags {
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+        pgn.push_str(&format!("[{} \"{}\"]\n", name, value));
+    }
+    pgn.push('\n');
+
+    let mut tokens = Vec::new();
+    let mut number = start.fullmove_number();
+    let mut color = start.active_color();
+    if color == Color::Black && !moves.is_empty() {
+        tokens.push(format!("{}...", number));
+    }
+    for step in moves {
+        if color == Color::White {
+            tokens.push(format!("{}.", number));
+        } else {
+            number += 1;
+        }
+        tokens.push(step.clone());
+        color = !color;
+    }
+    tokens.push(result.to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > LINE_WIDTH {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    pgn.push_str(&line);
+    pgn.push('\n');
+    pgn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::WinReason;
+
+    fn moves(list: &[&str]) -> Vec<String> {
+        list.iter().map(|step| step.to_string()).collect()
+    }
+
+    #[test]
+    fn tags_and_movetext() {
+        let board = Board::new();
+        let tags = [
+            ("Event", "Casual \"game\"".to_string()),
+            ("Result", "1-0".to_string()),
+        ];
+        let outcome = Some(GameOutcome::Win(Color::White, WinReason::Checkmate));
+        let pgn = write(
+            &tags,
+            &board.borrow(),
+            &moves(&["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7#"]),
+            result(outcome),
+        );
+        assert_eq!(
+            pgn,
+            "[Event \"Casual \\\"game\\\"\"]\n[Result \"1-0\"]\n\n\
+             1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0\n"
+        );
+    }
+
+    #[test]
+    fn black_moves_first() {
+        let board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPP1PP/RNBQKBNR b KQkq - 0 1").unwrap();
+        let pgn = write(&[], &board.borrow(), &moves(&["e5", "e4", "d5"]), "*");
+        assert_eq!(pgn, "\n1... e5 2. e4 d5 *\n");
+    }
+
+    #[test]
+    fn long_games_are_wrapped() {
+        let board = Board::new();
+        let pgn = write(&[], &board.borrow(), &vec!["Nf3".to_string(); 100], "*");
+        assert!(pgn.lines().all(|line| line.len() <= LINE_WIDTH));
+        assert!(pgn.trim_end().ends_with("50. Nf3 Nf3 *"));
+    }
+}
@@ -0,0 +1,37 @@
+use super::diagonal_movement::DiagonalMovement;
+use super::leaper_movement::LeaperMovement;
+use super::parallel_movement::ParallelMovement;
+use crate::pieces::Board;
+use crate::pieces::Piece;
+use crate::pieces::PieceMovement;
+use crate::pieces::StepChecker;
+use crate::positions::Position;
+
+// Queen and knight
+pub struct Amazon;
+
+impl DiagonalMovement for Amazon {}
+impl ParallelMovement for Amazon {}
+impl LeaperMovement for Amazon {
+    fn leap(&self) -> (i8, i8) {
+        (1, 2)
+    }
+}
+
+impl PieceMovement for Amazon {
+    fn new() -> Self {
+        Amazon {}
+    }
+
+    fn attacks(&self, piece: &Piece, target: &Position, board: &Board) -> bool {
+        <Self as DiagonalMovement>::attacks(self, piece, target, board)
+            || <Self as ParallelMovement>::attacks(self, piece, target, board)
+            || <Self as LeaperMovement>::attacks(self, piece, target, board)
+    }
+
+    fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
+        <Self as DiagonalMovement>::step_checker(self, diff)
+            .or_else(|| <Self as ParallelMovement>::step_checker(self, diff))
+            .or_else(|| <Self as LeaperMovement>::step_checker(self, diff))
+    }
+}
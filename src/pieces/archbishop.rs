@@ -0,0 +1,33 @@
+use super::diagonal_movement::DiagonalMovement;
+use super::leaper_movement::LeaperMovement;
+use crate::pieces::Board;
+use crate::pieces::Piece;
+use crate::pieces::PieceMovement;
+use crate::pieces::StepChecker;
+use crate::positions::Position;
+
+// Bishop and knight
+pub struct Archbishop;
+
+impl DiagonalMovement for Archbishop {}
+impl LeaperMovement for Archbishop {
+    fn leap(&self) -> (i8, i8) {
+        (1, 2)
+    }
+}
+
+impl PieceMovement for Archbishop {
+    fn new() -> Self {
+        Archbishop {}
+    }
+
+    fn attacks(&self, piece: &Piece, target: &Position, board: &Board) -> bool {
+        <Self as DiagonalMovement>::attacks(self, piece, target, board)
+            || <Self as LeaperMovement>::attacks(self, piece, target, board)
+    }
+
+    fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
+        <Self as DiagonalMovement>::step_checker(self, diff)
+            .or_else(|| <Self as LeaperMovement>::step_checker(self, diff))
+    }
+}
@@ -0,0 +1,29 @@
+use super::leaper_movement::LeaperMovement;
+use crate::pieces::Board;
+use crate::pieces::Piece;
+use crate::pieces::PieceMovement;
+use crate::pieces::StepChecker;
+use crate::positions::Position;
+
+// Long leaper, jumps one square one way and three the other
+pub struct Camel;
+
+impl LeaperMovement for Camel {
+    fn leap(&self) -> (i8, i8) {
+        (1, 3)
+    }
+}
+
+impl PieceMovement for Camel {
+    fn new() -> Self {
+        Camel {}
+    }
+
+    fn attacks(&self, piece: &Piece, target: &Position, board: &Board) -> bool {
+        <Self as LeaperMovement>::attacks(self, piece, target, board)
+    }
+
+    fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
+        <Self as LeaperMovement>::step_checker(self, diff)
+    }
+}
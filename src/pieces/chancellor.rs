@@ -0,0 +1,33 @@
+use super::leaper_movement::LeaperMovement;
+use super::parallel_movement::ParallelMovement;
+use crate::pieces::Board;
+use crate::pieces::Piece;
+use crate::pieces::PieceMovement;
+use crate::pieces::StepChecker;
+use crate::positions::Position;
+
+// Rook and knight
+pub struct Chancellor;
+
+impl ParallelMovement for Chancellor {}
+impl LeaperMovement for Chancellor {
+    fn leap(&self) -> (i8, i8) {
+        (1, 2)
+    }
+}
+
+impl PieceMovement for Chancellor {
+    fn new() -> Self {
+        Chancellor {}
+    }
+
+    fn attacks(&self, piece: &Piece, target: &Position, board: &Board) -> bool {
+        <Self as ParallelMovement>::attacks(self, piece, target, board)
+            || <Self as LeaperMovement>::attacks(self, piece, target, board)
+    }
+
+    fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
+        <Self as ParallelMovement>::step_checker(self, diff)
+            .or_else(|| <Self as LeaperMovement>::step_checker(self, diff))
+    }
+}
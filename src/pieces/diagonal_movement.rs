@@ -5,15 +5,13 @@ use crate::positions::*;
 use num::FromPrimitive;
 
 pub trait DiagonalMovement {
-    fn is_enemy_in_check(&self, piece: &Piece, board: &Board) -> bool {
-        [
-            uphill_path(&piece.position, Horizontal::HorA),
-            uphill_path(&piece.position, Horizontal::HorH),
-            downhill_path(&piece.position, Horizontal::HorA),
-            downhill_path(&piece.position, Horizontal::HorH),
-        ]
-        .iter()
-        .any(|path| super::find_check_state_in_path(path, piece, board))
+    fn attacks(&self, piece: &Piece, target: &Position, board: &Board) -> bool {
+        let path = match target - &piece.position {
+            (i, j) if i == j && i != 0 => uphill_path(&piece.position, target.hor),
+            (i, j) if i == -j && i != 0 => downhill_path(&piece.position, target.hor),
+            _ => return false,
+        };
+        super::path_is_empty(&path, board)
     }
 
     fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
@@ -0,0 +1,24 @@
+use super::hopper_movement::HopperMovement;
+use crate::pieces::Board;
+use crate::pieces::Piece;
+use crate::pieces::PieceMovement;
+use crate::pieces::StepChecker;
+use crate::positions::Position;
+
+pub struct Grasshopper;
+
+impl HopperMovement for Grasshopper {}
+
+impl PieceMovement for Grasshopper {
+    fn new() -> Self {
+        Grasshopper {}
+    }
+
+    fn attacks(&self, piece: &Piece, target: &Position, board: &Board) -> bool {
+        <Self as HopperMovement>::attacks(self, piece, target, board)
+    }
+
+    fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
+        <Self as HopperMovement>::step_checker(self, diff)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::pieces::Board;
+use crate::pieces::Piece;
+use crate::pieces::StepChecker;
+use crate::positions::Position;
+
+// Moves along queen lines, landing right behind the first piece on the way
+pub trait HopperMovement {
+    fn attacks(&self, piece: &Piece, target: &Position, board: &Board) -> bool {
+        lands_behind_hurdle(&piece.position, target, board)
+    }
+
+    fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
+        match diff {
+            (i, j) if (i == 0 || j == 0 || i == j || i == -j) && (i, j) != (0, 0) => {
+                Some(Box::new(check_hop))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn check_hop(me: &Piece, new_position: &Position, board: &Board) -> bool {
+    !super::dest_is_same_color(me, new_position, board)
+        && lands_behind_hurdle(&me.position, new_position, board)
+}
+
+fn lands_behind_hurdle(from: &Position, target: &Position, board: &Board) -> bool {
+    let (hor_diff, vert_diff) = target - from;
+    let is_line = hor_diff == 0 || vert_diff == 0 || hor_diff.abs() == vert_diff.abs();
+    let distance = hor_diff.abs().max(vert_diff.abs());
+    if !is_line || distance < 2 {
+        return false;
+    }
+    let (hor_step, vert_step) = (hor_diff.signum(), vert_diff.signum());
+    let square = |i: i8| from.transform(hor_step * i, vert_step * i);
+    let is_hurdle_there = square(distance - 1)
+        .map(|hurdle| !board.is_empty(&hurdle))
+        .unwrap_or(false);
+    is_hurdle_there
+        && (1..distance - 1)
+            .filter_map(square)
+            .all(|passed| board.is_empty(&passed))
+}
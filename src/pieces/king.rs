@@ -1,8 +1,9 @@
+use super::parallel_movement::horizontal_path;
 use crate::pieces::Board;
 use crate::pieces::Piece;
 use crate::pieces::PieceMovement;
 use crate::pieces::StepChecker;
-use crate::Position;
+use crate::positions::*;
 
 pub struct King;
 
@@ -11,14 +12,16 @@ impl PieceMovement for King {
         King {}
     }
 
-    fn is_enemy_in_check(&self, _piece: &Piece, _board: &Board) -> bool {
-        false
+    fn attacks(&self, piece: &Piece, target: &Position, _board: &Board) -> bool {
+        matches!(target - &piece.position, (-1..=1, -1..=1)) && *target != piece.position
     }
 
     fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
         match diff {
             (0, 0) => None,
+            (-1..=1, 0) => Some(Box::new(check_step_or_castling)),
             (-1..=1, -1..=1) => Some(Box::new(check)),
+            (_, 0) => Some(Box::new(check_castling)),
             _ => None,
         }
     }
@@ -27,3 +30,23 @@ impl PieceMovement for King {
 fn check(me: &Piece, new_position: &Position, board: &Board) -> bool {
     !super::dest_is_same_color(me, new_position, board)
 }
+
+// Chess960 king can castle by stepping onto an adjacent rook
+fn check_step_or_castling(me: &Piece, new_position: &Position, board: &Board) -> bool {
+    check(me, new_position, board) || check_castling(me, new_position, board)
+}
+
+// Only verifies the pieces involved, attacked squares are checked by the board
+fn check_castling(me: &Piece, new_position: &Position, board: &Board) -> bool {
+    let (rook_position, side) = match board.castling_rook(me, new_position) {
+        Some(castling) => castling,
+        None => return false,
+    };
+    let (king_to, rook_to) = board.castling_destinations(me.color, side);
+    let hors = [me.position.hor, rook_position.hor, king_to.hor, rook_to.hor];
+    let from = *hors.iter().min().unwrap();
+    let to = *hors.iter().max().unwrap();
+    horizontal_path(from, to, me.position.vert)
+        .iter()
+        .all(|square| *square == me.position || *square == rook_position || board.is_empty(square))
+}
@@ -0,0 +1,31 @@
+use crate::pieces::Board;
+use crate::pieces::Piece;
+use crate::pieces::StepChecker;
+use crate::positions::Position;
+
+// Jumps over anything to squares at the given distance, (1, 2) for knight
+pub trait LeaperMovement {
+    fn leap(&self) -> (i8, i8);
+
+    fn attacks(&self, piece: &Piece, target: &Position, _board: &Board) -> bool {
+        self.is_leap(target - &piece.position)
+    }
+
+    fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
+        if self.is_leap(diff) {
+            Some(Box::new(check_leap))
+        } else {
+            None
+        }
+    }
+
+    fn is_leap(&self, diff: (i8, i8)) -> bool {
+        let (hor, vert) = (diff.0.abs(), diff.1.abs());
+        let (short, long) = self.leap();
+        (hor, vert) == (short, long) || (hor, vert) == (long, short)
+    }
+}
+
+fn check_leap(me: &Piece, new_position: &Position, board: &Board) -> bool {
+    !super::dest_is_same_color(me, new_position, board)
+}
@@ -4,10 +4,17 @@ use crate::common::Color;
 
 use crate::positions::Position;
 
+mod amazon;
+mod archbishop;
 mod bishop;
+mod camel;
+mod chancellor;
 mod diagonal_movement;
+mod grasshopper;
+mod hopper_movement;
 mod king;
 mod knight;
+mod leaper_movement;
 mod parallel_movement;
 mod pawn;
 mod queen;
@@ -15,7 +22,7 @@ mod rook;
 
 type StepChecker = Box<dyn Fn(&Piece, &Position, &Board) -> bool>;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum PieceType {
     Pawn,
     Knight,
@@ -23,6 +30,47 @@ pub enum PieceType {
     Rook,
     Queen,
     King,
+    // Fairy pieces, they appear only in custom positions and variants which ask for them
+    Archbishop,
+    Chancellor,
+    Amazon,
+    Camel,
+    Grasshopper,
+}
+
+impl PieceType {
+    pub fn from_letter(letter: char) -> Option<PieceType> {
+        match letter.to_ascii_lowercase() {
+            'p' => Some(PieceType::Pawn),
+            'n' => Some(PieceType::Knight),
+            'b' => Some(PieceType::Bishop),
+            'r' => Some(PieceType::Rook),
+            'q' => Some(PieceType::Queen),
+            'k' => Some(PieceType::King),
+            'a' => Some(PieceType::Archbishop),
+            'c' => Some(PieceType::Chancellor),
+            'm' => Some(PieceType::Amazon),
+            'l' => Some(PieceType::Camel),
+            'g' => Some(PieceType::Grasshopper),
+            _ => None,
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            PieceType::Pawn => 'P',
+            PieceType::Knight => 'N',
+            PieceType::Bishop => 'B',
+            PieceType::Rook => 'R',
+            PieceType::Queen => 'Q',
+            PieceType::King => 'K',
+            PieceType::Archbishop => 'A',
+            PieceType::Chancellor => 'C',
+            PieceType::Amazon => 'M',
+            PieceType::Camel => 'L',
+            PieceType::Grasshopper => 'G',
+        }
+    }
 }
 
 pub struct Piece {
@@ -31,6 +79,7 @@ pub struct Piece {
     pub position: Position,
     pub movement: Box<dyn PieceMovement>,
     moved: bool,
+    promoted: bool,
 }
 
 impl Piece {
@@ -42,6 +91,11 @@ impl Piece {
             PieceType::Bishop => Box::new(bishop::Bishop::new()),
             PieceType::Queen => Box::new(queen::Queen::new()),
             PieceType::King => Box::new(king::King::new()),
+            PieceType::Archbishop => Box::new(archbishop::Archbishop::new()),
+            PieceType::Chancellor => Box::new(chancellor::Chancellor::new()),
+            PieceType::Amazon => Box::new(amazon::Amazon::new()),
+            PieceType::Camel => Box::new(camel::Camel::new()),
+            PieceType::Grasshopper => Box::new(grasshopper::Grasshopper::new()),
         };
         Piece {
             piece_type,
@@ -49,24 +103,44 @@ impl Piece {
             position,
             movement,
             moved: false,
+            promoted: false,
         }
     }
 
+    pub fn is_moved(&self) -> bool {
+        self.moved
+    }
+
     pub fn mark_as_moved(&mut self) {
         self.moved = true;
     }
 
+    pub fn is_promoted(&self) -> bool {
+        self.promoted
+    }
+
+    pub fn mark_as_promoted(&mut self) {
+        self.promoted = true;
+    }
+
+    // Compound pieces use knight-bishop/rook/queen symbols, black ones go 3 code points later.
+    // Camel and grasshopper are drawn as knight and queen turned sideways
     pub fn icon(&self) -> char {
-        let unicode = match self.piece_type {
-            PieceType::Pawn => 0x2659,
-            PieceType::Knight => 0x2658,
-            PieceType::Bishop => 0x2657,
-            PieceType::Rook => 0x2656,
-            PieceType::Queen => 0x2655,
-            PieceType::King => 0x2654,
+        let (unicode, black_offset) = match self.piece_type {
+            PieceType::Pawn => (0x2659, 6),
+            PieceType::Knight => (0x2658, 6),
+            PieceType::Bishop => (0x2657, 6),
+            PieceType::Rook => (0x2656, 6),
+            PieceType::Queen => (0x2655, 6),
+            PieceType::King => (0x2654, 6),
+            PieceType::Archbishop => (0x1FA52, 3),
+            PieceType::Chancellor => (0x1FA51, 3),
+            PieceType::Amazon => (0x1FA50, 3),
+            PieceType::Camel => (0x1FA04, 6),
+            PieceType::Grasshopper => (0x1FA01, 6),
         };
         std::char::from_u32(if self.color == Color::Black {
-            unicode + 6
+            unicode + black_offset
         } else {
             unicode
         })
@@ -74,13 +148,28 @@ impl Piece {
     }
 }
 
+impl Clone for Piece {
+    fn clone(&self) -> Self {
+        Piece {
+            moved: self.moved,
+            promoted: self.promoted,
+            ..Piece::new(self.piece_type, self.color, self.position)
+        }
+    }
+}
+
 pub trait PieceMovement {
     fn new() -> Self
     where
         Self: Sized;
-    fn is_enemy_in_check(&self, piece: &Piece, board: &Board) -> bool;
+    // Whether piece hits target square, no matter what stands on it
+    fn attacks(&self, piece: &Piece, target: &Position, board: &Board) -> bool;
     fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker>;
+    // Nothing can move onto the duck, not even to capture it
     fn can_move(&self, piece: &Piece, new_position: &Position, board: &Board) -> bool {
+        if board.duck() == Some(*new_position) {
+            return false;
+        }
         let diff = new_position - &piece.position;
         self.step_checker(diff)
             .map(|f| f(piece, new_position, board))
@@ -88,18 +177,6 @@ pub trait PieceMovement {
     }
 }
 
-fn verify_check_by_pos(piece: &Piece, other: Position, board: &Board) -> bool {
-    if let Some(other_piece) = board.cell(&other) {
-        verify_check(piece, other_piece)
-    } else {
-        false
-    }
-}
-
-fn verify_check(piece: &Piece, other: &Piece) -> bool {
-    other.color != piece.color && other.piece_type == PieceType::King
-}
-
 fn dest_is_same_color(piece: &Piece, new_position: &Position, board: &Board) -> bool {
     match board.cell(new_position) {
         None => false,
@@ -108,15 +185,5 @@ fn dest_is_same_color(piece: &Piece, new_position: &Position, board: &Board) ->
 }
 
 fn path_is_empty(path: &[Position], board: &Board) -> bool {
-    path[1..(path.len() - 1)]
-        .iter()
-        .all(|p| board.cell(&p).is_none())
-}
-
-fn find_check_state_in_path(path: &[Position], piece: &Piece, board: &Board) -> bool {
-    path.iter()
-        .skip(1)
-        .find_map(|pos| board.cell(&pos).as_ref())
-        .filter(|other| verify_check(piece, other))
-        .is_some()
+    path[1..(path.len() - 1)].iter().all(|p| board.is_empty(&p))
 }
@@ -5,15 +5,13 @@ use crate::positions::*;
 use num::FromPrimitive;
 
 pub trait ParallelMovement {
-    fn is_enemy_in_check(&self, piece: &Piece, board: &Board) -> bool {
-        [
-            horizontal_path(piece.position.hor, Horizontal::HorA, piece.position.vert),
-            horizontal_path(piece.position.hor, Horizontal::HorH, piece.position.vert),
-            vertical_path(piece.position.vert, Vertical::Vert1, piece.position.hor),
-            vertical_path(piece.position.vert, Vertical::Vert8, piece.position.hor),
-        ]
-        .iter()
-        .any(|path| super::find_check_state_in_path(path, piece, board))
+    fn attacks(&self, piece: &Piece, target: &Position, board: &Board) -> bool {
+        let path = match target - &piece.position {
+            (0, x) if x != 0 => vertical_path(piece.position.vert, target.vert, target.hor),
+            (x, 0) if x != 0 => horizontal_path(piece.position.hor, target.hor, target.vert),
+            _ => return false,
+        };
+        super::path_is_empty(&path, board)
     }
 
     fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
@@ -35,7 +33,7 @@ fn check_vertical(me: &Piece, new_position: &Position, board: &Board) -> bool {
     !super::dest_is_same_color(me, new_position, board) && super::path_is_empty(&path, board)
 }
 
-fn horizontal_path(from: Horizontal, to: Horizontal, vert: Vertical) -> Vec<Position> {
+pub fn horizontal_path(from: Horizontal, to: Horizontal, vert: Vertical) -> Vec<Position> {
     let (from_u8, to_u8) = if from > to {
         (to as u8, from as u8)
     } else {
@@ -2,8 +2,9 @@ use crate::pieces::Board;
 use crate::pieces::Color::*;
 use crate::pieces::Piece;
 use crate::pieces::PieceMovement;
+use crate::pieces::PieceType;
 use crate::pieces::StepChecker;
-use crate::Position;
+use crate::positions::Position;
 
 pub struct Pawn;
 
@@ -12,19 +13,9 @@ impl PieceMovement for Pawn {
         Pawn {}
     }
 
-    fn is_enemy_in_check(&self, piece: &Piece, board: &Board) -> bool {
+    fn attacks(&self, piece: &Piece, target: &Position, _board: &Board) -> bool {
         let mult = if piece.color == White { 1 } else { -1 };
-        if let Some(other) = piece.position.transform(-1, 1 * mult) {
-            if super::verify_check_by_pos(piece, other, board) {
-                return true;
-            }
-        }
-        if let Some(other) = piece.position.transform(1, 1 * mult) {
-            if super::verify_check_by_pos(piece, other, board) {
-                return true;
-            }
-        }
-        false
+        matches!(target - &piece.position, (-1, vert) | (1, vert) if vert == mult)
     }
 
     fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
@@ -49,13 +40,13 @@ fn check_up(me: &Piece, new_position: &Position, board: &Board) -> bool {
 fn check_long_up(me: &Piece, new_position: &Position, board: &Board) -> bool {
     me.color == White
         && check_long_forward(me, board.cell(new_position))
-        && board
-            .cell(&new_position.transform(0, -1).unwrap_or(me.position.clone()))
-            .is_none()
+        && board.is_empty(&new_position.transform(0, -1).unwrap_or(me.position.clone()))
 }
 
 fn check_diagonal_up(me: &Piece, new_position: &Position, board: &Board) -> bool {
-    me.color == White && check_diagonal(me, board.cell(new_position))
+    me.color == White
+        && (check_diagonal(me, board.cell(new_position))
+            || check_en_passant(me, new_position, board))
 }
 
 fn check_down(me: &Piece, new_position: &Position, board: &Board) -> bool {
@@ -65,13 +56,13 @@ fn check_down(me: &Piece, new_position: &Position, board: &Board) -> bool {
 fn check_long_down(me: &Piece, new_position: &Position, board: &Board) -> bool {
     me.color == Black
         && check_long_forward(me, board.cell(new_position))
-        && board
-            .cell(&new_position.transform(0, 1).unwrap_or(me.position.clone()))
-            .is_none()
+        && board.is_empty(&new_position.transform(0, 1).unwrap_or(me.position.clone()))
 }
 
 fn check_diagonal_down(me: &Piece, new_position: &Position, board: &Board) -> bool {
-    me.color == Black && check_diagonal(me, board.cell(new_position))
+    me.color == Black
+        && (check_diagonal(me, board.cell(new_position))
+            || check_en_passant(me, new_position, board))
 }
 
 fn check_forward(_: &Piece, dest: &Option<Piece>) -> bool {
@@ -87,3 +78,16 @@ fn check_diagonal(me: &Piece, dest: &Option<Piece>) -> bool {
         .map(|other| other.color != me.color)
         .unwrap_or(false)
 }
+
+fn check_en_passant(me: &Piece, new_position: &Position, board: &Board) -> bool {
+    let passed = Position {
+        hor: new_position.hor,
+        vert: me.position.vert,
+    };
+    board.en_passant() == Some(*new_position)
+        && board
+            .cell(&passed)
+            .as_ref()
+            .map(|other| other.color != me.color && other.piece_type == PieceType::Pawn)
+            .unwrap_or(false)
+}
@@ -4,6 +4,7 @@ use crate::pieces::Board;
 use crate::pieces::Piece;
 use crate::pieces::PieceMovement;
 use crate::pieces::StepChecker;
+use crate::positions::Position;
 
 pub struct Queen;
 
@@ -15,9 +16,9 @@ impl PieceMovement for Queen {
         Queen {}
     }
 
-    fn is_enemy_in_check(&self, piece: &Piece, board: &Board) -> bool {
-        <Self as DiagonalMovement>::is_enemy_in_check(self, piece, board)
-            || <Self as ParallelMovement>::is_enemy_in_check(self, piece, board)
+    fn attacks(&self, piece: &Piece, target: &Position, board: &Board) -> bool {
+        <Self as DiagonalMovement>::attacks(self, piece, target, board)
+            || <Self as ParallelMovement>::attacks(self, piece, target, board)
     }
 
     fn step_checker(&self, diff: (i8, i8)) -> Option<StepChecker> {
@@ -4,6 +4,9 @@ use crate::proto::chess::Cell;
 use num::FromPrimitive;
 use num_derive::FromPrimitive;
 
+// Largest board side, boards of variants can be any rectangle up to it
+pub const MAX_SIZE: u8 = 12;
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, FromPrimitive)]
 #[repr(u8)]
 pub enum Horizontal {
@@ -15,6 +18,10 @@ pub enum Horizontal {
     HorF,
     HorG,
     HorH,
+    HorI,
+    HorJ,
+    HorK,
+    HorL,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, FromPrimitive)]
@@ -28,6 +35,10 @@ pub enum Vertical {
     Vert6,
     Vert7,
     Vert8,
+    Vert9,
+    Vert10,
+    Vert11,
+    Vert12,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -72,17 +83,25 @@ impl std::ops::Sub for &Position {
 impl std::str::FromStr for Position {
     type Err = ChessError;
 
+    // File letter and rank number, like "e4" or "j10"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let hor = match s.bytes().nth(0) {
-            Some(x @ b'A'..=b'H') => FromPrimitive::from_u8(x - b'A').unwrap(),
-            Some(x @ b'a'..=b'h') => FromPrimitive::from_u8(x - b'a').unwrap(),
-            _ => return Err(InvalidPosition(s.to_string())),
+        let invalid = || InvalidPosition(s.to_string());
+        let hor = match s.bytes().next() {
+            Some(x) if x.is_ascii_alphabetic() => x.to_ascii_lowercase() - b'a',
+            _ => return Err(invalid()),
         };
-        let vert = match s.bytes().nth(1) {
-            Some(x @ b'1'..=b'8') => FromPrimitive::from_u8(x - b'1').unwrap(),
-            _ => return Err(InvalidPosition(s.to_string())),
+        let rank = &s[1..];
+        if rank.starts_with('0') || !rank.bytes().all(|x| x.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let vert = match rank.parse::<u8>() {
+            Ok(x) if x > 0 => x - 1,
+            _ => return Err(invalid()),
         };
-        Ok(Position { hor, vert })
+        match (FromPrimitive::from_u8(hor), FromPrimitive::from_u8(vert)) {
+            (Some(hor), Some(vert)) => Ok(Position { hor, vert }),
+            _ => Err(invalid()),
+        }
     }
 }
 
@@ -1,5 +1,11 @@
+mod bughouse_update;
 mod cell;
+mod game_setup;
 mod move_command;
+mod referee_message;
 
+pub use bughouse_update::BughouseUpdate;
 pub use cell::Cell;
+pub use game_setup::GameSetup;
 pub use move_command::MoveCommand;
+pub use referee_message::RefereeMessage;
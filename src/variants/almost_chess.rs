@@ -0,0 +1,57 @@
+use crate::pieces::PieceType;
+use crate::variants::Variant;
+
+pub struct AlmostChess;
+
+impl AlmostChess {
+    pub fn new() -> Self {
+        AlmostChess {}
+    }
+}
+
+impl Variant for AlmostChess {
+    fn name(&self) -> &'static str {
+        "almostchess"
+    }
+
+    fn description(&self) -> String {
+        "Almost Chess (queens are replaced by chancellors moving as rook and knight)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        "rnbckbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBCKBNR w KQkq - 0 1".to_string()
+    }
+
+    fn promotions(&self) -> Vec<PieceType> {
+        vec![
+            PieceType::Chancellor,
+            PieceType::Rook,
+            PieceType::Bishop,
+            PieceType::Knight,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::common::Color;
+    use std::rc::Rc;
+
+    #[test]
+    fn pawns_promote_to_chancellor() {
+        let variant = Rc::new(AlmostChess::new());
+        let board = Board::from_variant_fen("k7/6P1/8/8/8/8/8/K7 w - - 0 1", variant).unwrap();
+        assert!(board
+            .borrow_mut()
+            .move_piece(&"g7-g8q".parse().unwrap(), Color::White)
+            .is_err());
+        let result = board
+            .borrow_mut()
+            .move_piece(&"g7-g8".parse().unwrap(), Color::White)
+            .unwrap();
+        assert_eq!(result.promotion, Some(PieceType::Chancellor));
+        assert_eq!(board.borrow().to_fen(), "k5C1/8/8/8/8/8/8/K7 b - - 0 1");
+    }
+}
@@ -0,0 +1,161 @@
+use crate::board::Board;
+use crate::common::{ChessError, ChessResult, GameOutcome, StepResultData, WinReason};
+use crate::moves::Move;
+use crate::pieces::PieceType;
+use crate::variants::Variant;
+
+pub struct Antichess;
+
+impl Antichess {
+    pub fn new() -> Self {
+        Antichess {}
+    }
+}
+
+impl Variant for Antichess {
+    fn name(&self) -> &'static str {
+        "antichess"
+    }
+
+    fn description(&self) -> String {
+        "Antichess (captures are compulsory, lose all pieces to win)".to_string()
+    }
+
+    // No castling rights, king is just a regular piece here
+    fn starting_fen(&self) -> String {
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1".to_string()
+    }
+
+    fn has_special_goal(&self) -> bool {
+        true
+    }
+
+    fn is_legal(&self, _before: &Board, _after: &Board, result: &StepResultData) -> bool {
+        result.castling.is_none()
+    }
+
+    fn filter_moves(&self, board: &Board, moves: Vec<Move>) -> Vec<Move> {
+        if moves.iter().any(|step| board.is_capture(step)) {
+            moves
+                .into_iter()
+                .filter(|step| board.is_capture(step))
+                .collect()
+        } else {
+            moves
+        }
+    }
+
+    fn check_move(&self, board: &Board, result: &StepResultData) -> ChessResult<()> {
+        let is_capture_possible = result.eaten.is_none()
+            && board
+                .legal_moves(result.color)
+                .iter()
+                .any(|step| board.is_capture(step));
+        if is_capture_possible {
+            Err(ChessError::CaptureRequired(
+                result.step.from,
+                result.step.to,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn after_move(&self, _board: &mut Board, result: &mut StepResultData) {
+        result.checkers.clear();
+    }
+
+    fn outcome(&self, board: &Board, has_legal_moves: bool) -> Option<GameOutcome> {
+        let color = board.active_color();
+        if !board.pieces().any(|piece| piece.color == color) {
+            Some(GameOutcome::Win(color, WinReason::AllPiecesLost))
+        } else if !has_legal_moves {
+            Some(GameOutcome::Win(color, WinReason::Stalemated))
+        } else {
+            None
+        }
+    }
+
+    fn promotions(&self) -> Vec<PieceType> {
+        vec![
+            PieceType::Queen,
+            PieceType::Rook,
+            PieceType::Bishop,
+            PieceType::Knight,
+            PieceType::King,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Color;
+    use crate::variants;
+    use std::rc::Rc;
+
+    #[test]
+    fn captures_are_compulsory() {
+        let board = variants::board("antichess", "8/8/8/3p4/4P3/8/8/K7 w - - 0 1");
+        let moves = board.borrow().legal_moves(Color::White);
+        assert_eq!(moves, vec!["e4-d5".parse().unwrap()]);
+        let error = board
+            .borrow_mut()
+            .move_piece(&"a1-a2".parse().unwrap(), Color::White)
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Can't move from A1 to A2, capture is required"
+        );
+    }
+
+    #[test]
+    fn king_has_no_royal_status() {
+        let board = variants::board("antichess", "k7/8/8/8/8/8/1P6/r3K3 w - - 0 1");
+        let result = board
+            .borrow_mut()
+            .move_piece(&"b2-b3".parse().unwrap(), Color::White)
+            .unwrap();
+        assert!(!result.is_check());
+        let promotion = variants::board("antichess", "8/1P6/8/8/8/8/8/k7 w - - 0 1");
+        let result = promotion
+            .borrow_mut()
+            .move_piece(&"b7-b8k".parse().unwrap(), Color::White)
+            .unwrap();
+        assert_eq!(result.promotion, Some(PieceType::King));
+    }
+
+    #[test]
+    fn losing_all_pieces_wins() {
+        let board = variants::board("antichess", "8/8/8/8/8/8/1p6/R7 w - - 0 1");
+        board
+            .borrow_mut()
+            .move_piece(&"a1-a2".parse().unwrap(), Color::White)
+            .unwrap();
+        let result = board
+            .borrow_mut()
+            .move_piece(&"b2-b1q".parse().unwrap(), Color::Black)
+            .unwrap();
+        assert_eq!(result.outcome, None);
+        let result = board
+            .borrow_mut()
+            .move_piece(&"a2-a1".parse().unwrap(), Color::White)
+            .unwrap();
+        assert_eq!(result.outcome, None);
+        let result = board
+            .borrow_mut()
+            .move_piece(&"b1-a1".parse().unwrap(), Color::Black)
+            .unwrap();
+        assert_eq!(
+            result.outcome,
+            Some(GameOutcome::Win(Color::White, WinReason::AllPiecesLost))
+        );
+    }
+
+    #[test]
+    fn initial_perft() {
+        let board = Board::new_variant(Rc::new(Antichess::new())).unwrap();
+        assert_eq!(board.borrow().perft(3), 8067);
+    }
+}
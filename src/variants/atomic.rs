@@ -0,0 +1,166 @@
+use crate::board::Board;
+use crate::common::{Color, DrawReason, GameOutcome, StepResultData, WinReason};
+use crate::pieces::PieceType;
+use crate::positions::Position;
+use crate::variants::standard::Standard;
+use crate::variants::{is_castling_path_attacked, Variant};
+
+pub struct Atomic;
+
+impl Atomic {
+    pub fn new() -> Self {
+        Atomic {}
+    }
+}
+
+impl Variant for Atomic {
+    fn name(&self) -> &'static str {
+        "atomic"
+    }
+
+    fn description(&self) -> String {
+        "Atomic (captures explode, blow up the enemy king to win)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        Standard::new().starting_fen()
+    }
+
+    // Exploding the enemy king wins even if own king is left in check
+    fn is_legal(&self, before: &Board, after: &Board, result: &StepResultData) -> bool {
+        let color = result.color;
+        if result.piece_type == PieceType::King && result.eaten.is_some() {
+            return false;
+        }
+        if after.king_position(color).is_none() {
+            return false;
+        }
+        if after.king_position(!color).is_none() {
+            return true;
+        }
+        if is_castling_path_attacked(before, result, |square| is_attacked(before, square, !color)) {
+            return false;
+        }
+        !is_in_check(after, color)
+    }
+
+    fn after_move(&self, board: &mut Board, result: &mut StepResultData) {
+        if result.eaten.is_none() {
+            return;
+        }
+        let center = result.step.to;
+        result.exploded.extend(board.extract_piece(&center));
+        for hor in -1..=1 {
+            for vert in -1..=1 {
+                let square = Position::at(center.hor as i8 + hor, center.vert as i8 + vert);
+                let is_exploding = square
+                    .and_then(|square| board.cell(&square).as_ref())
+                    .map(|piece| piece.piece_type != PieceType::Pawn)
+                    .unwrap_or(false);
+                if is_exploding {
+                    result
+                        .exploded
+                        .extend(board.extract_piece(&square.unwrap()));
+                }
+            }
+        }
+        result.checkers = match board.king_position(!result.color) {
+            Some(king) if is_in_check(board, !result.color) => {
+                attackers(board, &king, result.color)
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    fn outcome(&self, board: &Board, has_legal_moves: bool) -> Option<GameOutcome> {
+        let color = board.active_color();
+        if board.king_position(color).is_none() {
+            Some(GameOutcome::Win(!color, WinReason::KingExploded))
+        } else if has_legal_moves {
+            None
+        } else if is_in_check(board, color) {
+            Some(GameOutcome::Win(!color, WinReason::Checkmate))
+        } else {
+            Some(GameOutcome::Draw(DrawReason::Stalemate))
+        }
+    }
+}
+
+// Kings can't capture, so they never attack anything
+fn attackers(board: &Board, square: &Position, color: Color) -> Vec<Position> {
+    board
+        .attackers_of(square, color)
+        .iter()
+        .filter(|piece| piece.piece_type != PieceType::King)
+        .map(|piece| piece.position)
+        .collect()
+}
+
+// King standing next to the enemy king can't be taken, the capture would explode both of them
+fn is_attacked(board: &Board, square: &Position, color: Color) -> bool {
+    let is_next_to_king = board
+        .king_position(color)
+        .map(|king| {
+            let (hor_diff, vert_diff) = &king - square;
+            hor_diff.abs() <= 1 && vert_diff.abs() <= 1
+        })
+        .unwrap_or(false);
+    !is_next_to_king && !attackers(board, square, color).is_empty()
+}
+
+fn is_in_check(board: &Board, color: Color) -> bool {
+    board
+        .king_position(color)
+        .map(|king| is_attacked(board, &king, !color))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants;
+    use std::rc::Rc;
+
+    #[test]
+    fn capture_explodes_neighbours_but_not_pawns() {
+        let board = variants::board("atomic", "4k3/8/8/2npb3/3r4/8/3R4/4K3 w - - 0 1");
+        let step = "d2-d4".parse().unwrap();
+        let result = board.borrow_mut().move_piece(&step, Color::White).unwrap();
+        let exploded: Vec<PieceType> = result
+            .exploded
+            .iter()
+            .map(|piece| piece.piece_type)
+            .collect();
+        assert_eq!(
+            exploded,
+            vec![PieceType::Rook, PieceType::Knight, PieceType::Bishop]
+        );
+        assert_eq!(board.borrow().to_fen(), "4k3/8/8/3p4/8/8/8/4K3 b - - 0 1");
+    }
+
+    #[test]
+    fn kings_cannot_capture_and_touching_kings_are_safe() {
+        let board = variants::board("atomic", "8/8/8/8/8/3k4/3p4/3K4 w - - 0 1");
+        let moves = board.borrow().legal_moves(Color::White);
+        assert!(moves.iter().all(|step| step.to != "d2".parse().unwrap()));
+        let touching = variants::board("atomic", "8/8/8/8/8/8/3kq3/3K4 w - - 0 1");
+        assert!(!is_in_check(&touching.borrow(), Color::White));
+    }
+
+    #[test]
+    fn exploding_the_king_wins() {
+        let board = variants::board("atomic", "4k3/3p4/8/8/8/8/8/3RK3 w - - 0 1");
+        let step = "d1-d7".parse().unwrap();
+        let result = board.borrow_mut().move_piece(&step, Color::White).unwrap();
+        assert_eq!(
+            result.outcome,
+            Some(GameOutcome::Win(Color::White, WinReason::KingExploded))
+        );
+    }
+
+    #[test]
+    fn initial_perft() {
+        let board = Board::new_variant(Rc::new(Atomic::new())).unwrap();
+        assert_eq!(board.borrow().perft(3), 8902);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::board::Board;
+use crate::common::{Color, StepResultData};
+use crate::variants::crazyhouse::{self, Crazyhouse};
+use crate::variants::Variant;
+
+pub struct Bughouse;
+
+impl Bughouse {
+    pub fn new() -> Self {
+        Bughouse {}
+    }
+}
+
+impl Variant for Bughouse {
+    fn name(&self) -> &'static str {
+        "bughouse"
+    }
+
+    fn description(&self) -> String {
+        "Bughouse (two boards, pieces you capture go to your partner's pocket)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        Crazyhouse::new().starting_fen()
+    }
+
+    fn has_pockets(&self) -> bool {
+        true
+    }
+
+    fn is_team_game(&self) -> bool {
+        true
+    }
+
+    fn notes(&self, board: &Board) -> Vec<String> {
+        Crazyhouse::new().notes(board)
+    }
+}
+
+// Partner plays the other color on the other board, so the piece goes to that side's pocket
+pub fn pass_to_partner(partner_board: &mut Board, result: &StepResultData) {
+    if let Some(piece_type) = crazyhouse::pocketed_piece(result) {
+        partner_board.add_to_pocket(!result.color, piece_type);
+    }
+}
+
+// Seats are filled in the connection order: A white, A black, B white, B black
+pub fn seat(index: usize) -> (usize, Color) {
+    let color = if index % 2 == 1 {
+        Color::Black
+    } else {
+        Color::White
+    };
+    (index / 2, color)
+}
+
+pub fn board_letter(index: usize) -> char {
+    (b'A' + index as u8) as char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pieces::PieceType;
+    use crate::variants;
+    use std::rc::Rc;
+
+    #[test]
+    fn captured_pieces_go_to_partner() {
+        let board = variants::board("bughouse", "4k3/8/8/3q4/8/8/8/3QK3[] w - - 0 1");
+        let partner = Board::new_variant(Rc::new(Bughouse::new())).unwrap();
+        let result = board
+            .borrow_mut()
+            .move_piece(&"d1-d5".parse().unwrap(), Color::White)
+            .unwrap();
+        pass_to_partner(&mut partner.borrow_mut(), &result);
+        assert!(board.borrow().pocket(Color::White).is_empty());
+        assert_eq!(partner.borrow().pocket(Color::Black), &[PieceType::Queen]);
+        assert!(partner
+            .borrow_mut()
+            .move_piece(&"Q@e3".parse().unwrap(), Color::White)
+            .is_err());
+    }
+
+    #[test]
+    fn seats_in_connection_order() {
+        assert_eq!(seat(0), (0, Color::White));
+        assert_eq!(seat(1), (0, Color::Black));
+        assert_eq!(seat(2), (1, Color::White));
+        assert_eq!(seat(3), (1, Color::Black));
+    }
+}
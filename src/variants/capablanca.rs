@@ -0,0 +1,98 @@
+use crate::pieces::PieceType;
+use crate::variants::Variant;
+
+pub struct Capablanca;
+
+impl Capablanca {
+    pub fn new() -> Self {
+        Capablanca {}
+    }
+}
+
+impl Variant for Capablanca {
+    fn name(&self) -> &'static str {
+        "capablanca"
+    }
+
+    fn description(&self) -> String {
+        "Capablanca chess (10x8 board with archbishops and chancellors)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        "rnabqkbcnr/pppppppppp/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1".to_string()
+    }
+
+    fn dimensions(&self) -> (u8, u8) {
+        (10, 8)
+    }
+
+    fn promotions(&self) -> Vec<PieceType> {
+        vec![
+            PieceType::Queen,
+            PieceType::Chancellor,
+            PieceType::Archbishop,
+            PieceType::Rook,
+            PieceType::Bishop,
+            PieceType::Knight,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::common::{CastlingSide, Color};
+    use crate::variants;
+    use std::rc::Rc;
+
+    #[test]
+    fn king_castles_three_squares() {
+        let fen = "r4k3r/10/10/10/10/10/10/R4K3R w KQkq - 0 1";
+        let board = variants::board("capablanca", fen);
+        let result = board
+            .borrow_mut()
+            .move_piece(&"f1-i1".parse().unwrap(), Color::White)
+            .unwrap();
+        assert_eq!(result.castling, Some(CastlingSide::King));
+        assert_eq!(
+            board.borrow().to_fen(),
+            "r4k3r/10/10/10/10/10/10/R6RK1 b kq - 1 1"
+        );
+        let board = variants::board("capablanca", fen);
+        let step = board
+            .borrow()
+            .castling_move(Color::White, CastlingSide::Queen)
+            .unwrap();
+        board.borrow_mut().move_piece(&step, Color::White).unwrap();
+        assert_eq!(
+            board.borrow().to_fen(),
+            "r4k3r/10/10/10/10/10/10/2KR5R b kq - 1 1"
+        );
+    }
+
+    #[test]
+    fn wide_board_squares() {
+        let board = variants::board("capablanca", "4k5/10/10/10/10/10/10/4K4R w - - 0 1");
+        let moves = board.borrow().legal_moves(Color::White);
+        assert!(moves.contains(&"j1-j8".parse().unwrap()));
+        assert!(moves.iter().all(|step| board.borrow().contains(&step.to)));
+        assert!(board
+            .borrow_mut()
+            .move_piece(&"j1-k1".parse().unwrap(), Color::White)
+            .is_err());
+    }
+
+    #[test]
+    fn initial_perft() {
+        let board = Board::new_variant(Rc::new(Capablanca::new())).unwrap();
+        assert_eq!(board.borrow().perft(2), 784);
+    }
+
+    #[test]
+    #[ignore]
+    fn initial_perft_deep() {
+        let board = Board::new_variant(Rc::new(Capablanca::new())).unwrap();
+        assert_eq!(board.borrow().perft(3), 25_228);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::common::{ChessError, ChessResult};
+use crate::pieces::PieceType;
+use crate::variants::Variant;
+use rand::Rng;
+
+pub const POSITIONS_COUNT: u16 = 960;
+
+// Knights placements among five free squares left after bishops and queen
+const KNIGHTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+// First row from A to H for the position number in Scharnagl's numbering
+pub fn back_rank(index: u16) -> Option<[PieceType; 8]> {
+    if index >= POSITIONS_COUNT {
+        return None;
+    }
+    let mut result: [Option<PieceType>; 8] = [None; 8];
+    let mut n = index as usize;
+    result[n % 4 * 2 + 1] = Some(PieceType::Bishop);
+    n /= 4;
+    result[n % 4 * 2] = Some(PieceType::Bishop);
+    n /= 4;
+    place_on_free(&mut result, n % 6, PieceType::Queen);
+    n /= 6;
+    let (first_knight, second_knight) = KNIGHTS[n];
+    place_on_free(&mut result, second_knight, PieceType::Knight);
+    place_on_free(&mut result, first_knight, PieceType::Knight);
+    for piece_type in &[PieceType::Rook, PieceType::King, PieceType::Rook] {
+        place_on_free(&mut result, 0, *piece_type);
+    }
+    let mut back_rank = [PieceType::Pawn; 8];
+    for (cell, piece_type) in back_rank.iter_mut().zip(result.iter()) {
+        *cell = piece_type.unwrap();
+    }
+    Some(back_rank)
+}
+
+pub struct Chess960 {
+    position: u16,
+    back_rank: String,
+}
+
+impl Chess960 {
+    pub fn new(position: u16) -> ChessResult<Self> {
+        let back_rank = back_rank(position)
+            .ok_or_else(|| ChessError::InvalidInput(position.to_string()))?
+            .iter()
+            .map(|piece_type| piece_type.letter())
+            .collect();
+        Ok(Chess960 {
+            position,
+            back_rank,
+        })
+    }
+}
+
+impl Variant for Chess960 {
+    fn name(&self) -> &'static str {
+        "chess960"
+    }
+
+    fn description(&self) -> String {
+        format!("Chess960 position {}", self.position)
+    }
+
+    fn starting_fen(&self) -> String {
+        format!(
+            "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1",
+            self.back_rank.to_ascii_lowercase(),
+            self.back_rank
+        )
+    }
+
+    fn is_chess960(&self) -> bool {
+        true
+    }
+}
+
+pub fn random_index() -> u16 {
+    rand::thread_rng().gen_range(0, POSITIONS_COUNT)
+}
+
+fn place_on_free(row: &mut [Option<PieceType>; 8], free_index: usize, piece_type: PieceType) {
+    if let Some(cell) = row.iter_mut().filter(|cell| cell.is_none()).nth(free_index) {
+        *cell = Some(piece_type);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STANDARD_POSITION: u16 = 518;
+
+    fn back_rank_letters(index: u16) -> String {
+        back_rank(index)
+            .unwrap()
+            .iter()
+            .map(|piece_type| piece_type.letter())
+            .collect()
+    }
+
+    #[test]
+    fn known_positions() {
+        assert_eq!(back_rank_letters(0), "BBQNNRKR");
+        assert_eq!(back_rank_letters(STANDARD_POSITION), "RNBQKBNR");
+        assert_eq!(back_rank_letters(959), "RKRNNQBB");
+        assert!(back_rank(POSITIONS_COUNT).is_none());
+        assert!(crate::variants::create("chess960", 65536 + 518).is_err());
+    }
+
+    #[test]
+    fn all_positions_are_valid_and_distinct() {
+        let mut seen = std::collections::HashSet::new();
+        for index in 0..POSITIONS_COUNT {
+            let letters = back_rank_letters(index);
+            let king = letters.find('K').unwrap();
+            assert!(letters.find('R').unwrap() < king && letters.rfind('R').unwrap() > king);
+            let bishops: Vec<usize> = letters.match_indices('B').map(|(i, _)| i).collect();
+            assert_eq!(bishops.len(), 2);
+            assert_ne!(bishops[0] % 2, bishops[1] % 2);
+            assert!(seen.insert(letters));
+        }
+    }
+}
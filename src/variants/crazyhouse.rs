@@ -0,0 +1,128 @@
+use crate::board::Board;
+use crate::common::{Color, StepResultData};
+use crate::pieces::PieceType;
+use crate::variants::Variant;
+
+pub struct Crazyhouse;
+
+impl Crazyhouse {
+    pub fn new() -> Self {
+        Crazyhouse {}
+    }
+}
+
+impl Variant for Crazyhouse {
+    fn name(&self) -> &'static str {
+        "crazyhouse"
+    }
+
+    fn description(&self) -> String {
+        "Crazyhouse (captured pieces can be dropped back as N@f3)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1".to_string()
+    }
+
+    fn has_pockets(&self) -> bool {
+        true
+    }
+
+    // Captured piece changes sides
+    fn after_move(&self, board: &mut Board, result: &mut StepResultData) {
+        if let Some(piece_type) = pocketed_piece(result) {
+            board.add_to_pocket(result.color, piece_type);
+        }
+    }
+
+    fn notes(&self, board: &Board) -> Vec<String> {
+        let pocket = |color| -> String {
+            board
+                .pocket(color)
+                .iter()
+                .map(|piece_type| piece_type.letter())
+                .collect()
+        };
+        vec![
+            "Pockets:".to_string(),
+            format!("White: {}", pocket(Color::White)),
+            format!("Black: {}", pocket(Color::Black)),
+        ]
+    }
+}
+
+// Piece to put into a pocket after the capture, promoted ones become pawns again
+pub fn pocketed_piece(result: &StepResultData) -> Option<PieceType> {
+    result.eaten.as_ref().map(|eaten| {
+        if eaten.is_promoted() {
+            PieceType::Pawn
+        } else {
+            eaten.piece_type
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::Move;
+    use crate::variants;
+
+    #[test]
+    fn captured_pieces_go_to_pocket() {
+        let board = variants::board("crazyhouse", "4k3/1P6/8/8/8/8/1r6/4K3[] w - - 0 1");
+        let step = "b7-b8q".parse().unwrap();
+        board.borrow_mut().move_piece(&step, Color::White).unwrap();
+        assert_eq!(
+            board.borrow().to_fen(),
+            "1Q~2k3/8/8/8/8/8/1r6/4K3[] b - - 0 1"
+        );
+        let step = "b2-b8".parse().unwrap();
+        board.borrow_mut().move_piece(&step, Color::Black).unwrap();
+        assert_eq!(board.borrow().pocket(Color::Black), &[PieceType::Pawn]);
+        let step = "e1-d2".parse().unwrap();
+        board.borrow_mut().move_piece(&step, Color::White).unwrap();
+        assert_eq!(
+            board.borrow().to_fen(),
+            "1r2k3/8/8/8/8/8/3K4/8[p] b - - 1 2"
+        );
+    }
+
+    #[test]
+    fn drops() {
+        let board = variants::board("crazyhouse", "4k3/8/8/8/8/8/8/4K3[NPn] w - - 0 1");
+        let moves = board.borrow().legal_moves(Color::White);
+        let pawn_drops = moves
+            .iter()
+            .filter(|step| step.drop == Some(PieceType::Pawn))
+            .count();
+        assert_eq!(pawn_drops, 48);
+        assert!(!moves.contains(&"P@e8".parse::<Move>().unwrap()));
+        assert!(board
+            .borrow_mut()
+            .move_piece(&"q@d4".parse().unwrap(), Color::White)
+            .is_err());
+        let result = board
+            .borrow_mut()
+            .move_piece(&"N@f6".parse().unwrap(), Color::White)
+            .unwrap();
+        assert!(result.is_check());
+        assert_eq!(
+            board.borrow().to_fen(),
+            "4k3/8/5N2/8/8/8/8/4K3[Pn] b - - 1 1"
+        );
+        let dropped_pawn = variants::board("crazyhouse", "4k3/8/8/8/8/8/8/4K3[P] w - - 0 1");
+        dropped_pawn
+            .borrow_mut()
+            .move_piece(&"@a2".parse().unwrap(), Color::White)
+            .unwrap();
+        dropped_pawn
+            .borrow_mut()
+            .move_piece(&"e8-e7".parse().unwrap(), Color::Black)
+            .unwrap();
+        assert!(dropped_pawn
+            .borrow_mut()
+            .move_piece(&"a2-a4".parse().unwrap(), Color::White)
+            .is_ok());
+    }
+}
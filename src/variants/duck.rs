@@ -0,0 +1,127 @@
+use crate::board::Board;
+use crate::common::{GameOutcome, StepResultData, WinReason};
+use crate::pieces::PieceType;
+use crate::variants::Variant;
+
+pub struct Duck;
+
+impl Duck {
+    pub fn new() -> Self {
+        Duck {}
+    }
+}
+
+impl Variant for Duck {
+    fn name(&self) -> &'static str {
+        "duck"
+    }
+
+    fn description(&self) -> String {
+        "Duck chess (place the duck after every move, capture the king to win)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string()
+    }
+
+    fn has_duck(&self) -> bool {
+        true
+    }
+
+    // There is no check, king can be left under attack and captured
+    fn is_legal(&self, _before: &Board, _after: &Board, _result: &StepResultData) -> bool {
+        true
+    }
+
+    fn after_move(&self, _board: &mut Board, result: &mut StepResultData) {
+        result.checkers.clear();
+    }
+
+    // Side without moves wins, just like in Antichess
+    fn outcome(&self, board: &Board, has_legal_moves: bool) -> Option<GameOutcome> {
+        let color = board.active_color();
+        let has_king = board
+            .pieces()
+            .any(|piece| piece.color == color && piece.piece_type == PieceType::King);
+        if !has_king {
+            Some(GameOutcome::Win(!color, WinReason::KingCaptured))
+        } else if !has_legal_moves {
+            Some(GameOutcome::Win(color, WinReason::Stalemated))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{ChessError, Color};
+    use crate::positions::Position;
+    use crate::variants;
+    use std::rc::Rc;
+
+    #[test]
+    fn duck_blocks_both_sides() {
+        let board = variants::board("duck", "r3k3/8/8/8/*7/8/8/R3K3 w - - 0 1");
+        assert_eq!(board.borrow().to_fen(), "r3k3/8/8/8/*7/8/8/R3K3 w - - 0 1");
+        let duck: Position = "a4".parse().unwrap();
+        let moves = board.borrow().legal_moves(Color::White);
+        assert!(moves.iter().all(|step| step.to.vert < duck.vert));
+        assert!(moves.iter().all(|step| step.duck != Some(duck)));
+        assert!(moves.iter().all(|step| step.duck != Some(step.to)));
+        let moves = board.borrow().legal_moves(Color::Black);
+        assert!(moves.iter().all(|step| step.to.vert > duck.vert));
+    }
+
+    #[test]
+    fn every_move_comes_with_every_empty_square() {
+        let board = Board::new_variant(Rc::new(Duck::new())).unwrap();
+        let moves = board.borrow().legal_moves(Color::White);
+        assert_eq!(moves.len(), 20 * 32);
+        // Square left by the moved piece is free for the duck too
+        let board = board.borrow();
+        assert!(moves.iter().all(|step| {
+            let duck = step.duck.unwrap();
+            duck != step.to && (duck == step.from || board.cell(&duck).is_none())
+        }));
+    }
+
+    #[test]
+    fn duck_has_to_be_moved() {
+        let board = variants::board("duck", "4k3/8/8/8/*7/8/8/R3K3 w - - 0 1");
+        let result = board
+            .borrow_mut()
+            .move_piece(&"a1-a3".parse().unwrap(), Color::White);
+        assert!(matches!(result, Err(ChessError::DuckRequired(_, _))));
+        for step in ["a1-a3,a4", "a1-a3,a3", "a1-a3,e1"].iter() {
+            let result = board
+                .borrow_mut()
+                .move_piece(&step.parse().unwrap(), Color::White);
+            assert!(matches!(result, Err(ChessError::InvalidMove(_, _))));
+        }
+        board
+            .borrow_mut()
+            .move_piece(&"a1-a3,a1".parse().unwrap(), Color::White)
+            .unwrap();
+        assert_eq!(board.borrow().to_fen(), "4k3/8/8/8/8/R7/8/*3K3 b - - 1 1");
+    }
+
+    #[test]
+    fn king_can_be_captured() {
+        let board = variants::board("duck", "4r2k/8/8/8/8/8/8/4K3 w - - 0 1");
+        let result = board
+            .borrow_mut()
+            .move_piece(&"e1-e2,d4".parse().unwrap(), Color::White)
+            .unwrap();
+        assert!(!result.is_check());
+        let result = board
+            .borrow_mut()
+            .move_piece(&"e8-e2,a1".parse().unwrap(), Color::Black)
+            .unwrap();
+        assert_eq!(
+            result.outcome,
+            Some(GameOutcome::Win(Color::Black, WinReason::KingCaptured))
+        );
+    }
+}
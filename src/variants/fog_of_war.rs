@@ -0,0 +1,116 @@
+use crate::board::Board;
+use crate::common::{Color, DrawReason, GameOutcome, StepResultData, WinReason};
+use crate::pieces::PieceType;
+use crate::positions::Position;
+use crate::variants::standard::Standard;
+use crate::variants::Variant;
+
+pub struct FogOfWar;
+
+impl FogOfWar {
+    pub fn new() -> Self {
+        FogOfWar {}
+    }
+}
+
+impl Variant for FogOfWar {
+    fn name(&self) -> &'static str {
+        "fogofwar"
+    }
+
+    fn description(&self) -> String {
+        "Fog of War (you see only squares your pieces can move to, capture the king to win)"
+            .to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        Standard::new().starting_fen()
+    }
+
+    // There is no check, king can be left under attack and captured
+    fn is_legal(&self, _before: &Board, _after: &Board, _result: &StepResultData) -> bool {
+        true
+    }
+
+    fn after_move(&self, _board: &mut Board, result: &mut StepResultData) {
+        result.checkers.clear();
+    }
+
+    fn outcome(&self, board: &Board, has_legal_moves: bool) -> Option<GameOutcome> {
+        let color = board.active_color();
+        let has_king = board
+            .pieces()
+            .any(|piece| piece.color == color && piece.piece_type == PieceType::King);
+        if !has_king {
+            Some(GameOutcome::Win(!color, WinReason::KingCaptured))
+        } else if !has_legal_moves {
+            Some(GameOutcome::Draw(DrawReason::Stalemate))
+        } else {
+            None
+        }
+    }
+
+    fn visible_squares(&self, board: &Board, color: Color) -> Option<Vec<Position>> {
+        let mut visible: Vec<Position> = board
+            .pieces()
+            .filter(|piece| piece.color == color)
+            .map(|piece| piece.position)
+            .collect();
+        for step in board.legal_moves(color) {
+            if !visible.contains(&step.to) {
+                visible.push(step.to);
+            }
+        }
+        Some(visible)
+    }
+
+    fn shows_fog(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants;
+    use std::rc::Rc;
+
+    #[test]
+    fn hidden_pieces_are_masked() {
+        let board = Board::new_variant(Rc::new(FogOfWar::new())).unwrap();
+        let board = board.borrow();
+        let visible = FogOfWar::new()
+            .visible_squares(&board, Color::White)
+            .unwrap();
+        assert_eq!(visible.len(), 16 + 16);
+        assert_eq!(
+            board.masked(&visible).to_fen(),
+            "8/8/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1"
+        );
+        let board = variants::board("fogofwar", "4k3/8/8/8/8/8/8/R3K2q w - - 0 1");
+        let visible = FogOfWar::new()
+            .visible_squares(&board.borrow(), Color::White)
+            .unwrap();
+        assert!(!visible.contains(&"h1".parse().unwrap()));
+        assert!(!visible.contains(&"e8".parse().unwrap()));
+        assert!(visible.contains(&"a8".parse().unwrap()));
+    }
+
+    #[test]
+    fn king_can_be_captured() {
+        let board = variants::board("fogofwar", "7k/8/8/8/8/8/8/4K2R b - - 0 1");
+        let result = board
+            .borrow_mut()
+            .move_piece(&"h8-h7".parse().unwrap(), Color::Black)
+            .unwrap();
+        assert!(!result.is_check());
+        let result = board
+            .borrow_mut()
+            .move_piece(&"h1-h7".parse().unwrap(), Color::White)
+            .unwrap();
+        assert_eq!(
+            result.outcome,
+            Some(GameOutcome::Win(Color::White, WinReason::KingCaptured))
+        );
+    }
+}
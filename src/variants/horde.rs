@@ -0,0 +1,78 @@
+use crate::board::Board;
+use crate::common::{Color, GameOutcome, WinReason};
+use crate::positions::Vertical;
+use crate::variants::{self, Variant};
+
+pub struct Horde;
+
+impl Horde {
+    pub fn new() -> Self {
+        Horde {}
+    }
+}
+
+impl Variant for Horde {
+    fn name(&self) -> &'static str {
+        "horde"
+    }
+
+    fn description(&self) -> String {
+        "Horde (36 white pawns without a king against the regular army)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1".to_string()
+    }
+
+    // White has no king to be checked, so only black can be mated
+    fn outcome(&self, board: &Board, has_legal_moves: bool) -> Option<GameOutcome> {
+        if !board.pieces().any(|piece| piece.color == Color::White) {
+            Some(GameOutcome::Win(Color::Black, WinReason::AllPiecesCaptured))
+        } else {
+            variants::standard_outcome(board, has_legal_moves)
+        }
+    }
+
+    fn double_step_ranks(&self, color: Color) -> Vec<Vertical> {
+        match color {
+            Color::White => vec![Vertical::Vert1, Vertical::Vert2],
+            Color::Black => vec![Vertical::Vert7],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn first_rank_pawns_double_step() {
+        let board = variants::board("horde", "4k3/8/8/8/8/8/8/P7 w - - 0 1");
+        assert!(board
+            .borrow_mut()
+            .move_piece(&"a1-a3".parse().unwrap(), Color::White)
+            .is_ok());
+        assert_eq!(board.borrow().to_fen(), "4k3/8/8/8/8/P7/8/8 b - a2 0 1");
+    }
+
+    #[test]
+    fn capturing_all_pieces_wins() {
+        let board = variants::board("horde", "4k3/8/8/8/8/8/rP6/8 b - - 0 1");
+        assert_eq!(board.borrow().is_in_check_state(), None);
+        let result = board
+            .borrow_mut()
+            .move_piece(&"a2-b2".parse().unwrap(), Color::Black)
+            .unwrap();
+        assert_eq!(
+            result.outcome,
+            Some(GameOutcome::Win(Color::Black, WinReason::AllPiecesCaptured))
+        );
+    }
+
+    #[test]
+    fn initial_perft() {
+        let board = Board::new_variant(Rc::new(Horde::new())).unwrap();
+        assert_eq!(board.borrow().perft(3), 1274);
+    }
+}
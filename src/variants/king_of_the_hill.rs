@@ -0,0 +1,74 @@
+use crate::board::Board;
+use crate::common::{GameOutcome, WinReason};
+use crate::positions::Position;
+use crate::variants::standard::Standard;
+use crate::variants::{self, Variant};
+
+const HILL: [(i8, i8); 4] = [(3, 3), (3, 4), (4, 3), (4, 4)];
+
+pub struct KingOfTheHill;
+
+impl KingOfTheHill {
+    pub fn new() -> Self {
+        KingOfTheHill {}
+    }
+}
+
+impl Variant for KingOfTheHill {
+    fn name(&self) -> &'static str {
+        "kingofthehill"
+    }
+
+    fn description(&self) -> String {
+        "King of the Hill (bring your king to the center to win)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        Standard::new().starting_fen()
+    }
+
+    fn has_special_goal(&self) -> bool {
+        true
+    }
+
+    // Only the side that just moved could have reached the hill
+    fn outcome(&self, board: &Board, has_legal_moves: bool) -> Option<GameOutcome> {
+        let color = !board.active_color();
+        match board.king_position(color) {
+            Some(king) if self.marked_squares().contains(&king) => {
+                Some(GameOutcome::Win(color, WinReason::KingOfTheHill))
+            }
+            _ => variants::standard_outcome(board, has_legal_moves),
+        }
+    }
+
+    fn marked_squares(&self) -> Vec<Position> {
+        HILL.iter()
+            .filter_map(|(hor, vert)| Position::at(*hor, *vert))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Color;
+    use std::rc::Rc;
+
+    #[test]
+    fn king_wins_on_the_hill() {
+        let variant = Rc::new(KingOfTheHill::new());
+        let board = Board::from_variant_fen("4k3/8/8/8/8/4K3/8/8 w - - 0 1", variant).unwrap();
+        let step = "e3-d3".parse().unwrap();
+        let result = board.borrow_mut().move_piece(&step, Color::White).unwrap();
+        assert_eq!(result.outcome, None);
+        let step = "e8-e7".parse().unwrap();
+        board.borrow_mut().move_piece(&step, Color::Black).unwrap();
+        let step = "d3-d4".parse().unwrap();
+        let result = board.borrow_mut().move_piece(&step, Color::White).unwrap();
+        assert_eq!(
+            result.outcome,
+            Some(GameOutcome::Win(Color::White, WinReason::KingOfTheHill))
+        );
+    }
+}
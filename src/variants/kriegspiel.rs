@@ -0,0 +1,167 @@
+use crate::board::Board;
+use crate::common::{Color, StepResultData};
+use crate::pieces::PieceType;
+use crate::positions::Position;
+use crate::variants::standard::Standard;
+use crate::variants::Variant;
+
+pub struct Kriegspiel;
+
+impl Kriegspiel {
+    pub fn new() -> Self {
+        Kriegspiel {}
+    }
+}
+
+impl Variant for Kriegspiel {
+    fn name(&self) -> &'static str {
+        "kriegspiel"
+    }
+
+    fn description(&self) -> String {
+        "Kriegspiel (only own pieces are visible, server is the referee)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        Standard::new().starting_fen()
+    }
+
+    fn visible_squares(&self, board: &Board, color: Color) -> Option<Vec<Position>> {
+        Some(
+            board
+                .pieces()
+                .filter(|piece| piece.color == color)
+                .map(|piece| piece.position)
+                .collect(),
+        )
+    }
+
+    fn announcements(&self, board: &Board, result: &StepResultData) -> Vec<String> {
+        let mut announcements = Vec::new();
+        if let Some(eaten) = &result.eaten {
+            announcements.push(format!("Capture at {}", eaten.position));
+        }
+        if let Some(king) = board.king_position(!result.color) {
+            for checker in &result.checkers {
+                announcements.push(check_direction(board, &king, checker).to_string());
+            }
+        }
+        let mut pawn_tries: Vec<(Position, Position)> = board
+            .legal_moves(board.active_color())
+            .iter()
+            .filter(|step| board.is_capture(step))
+            .filter(|step| {
+                board
+                    .cell(&step.from)
+                    .as_ref()
+                    .map(|piece| piece.piece_type == PieceType::Pawn)
+                    .unwrap_or(false)
+            })
+            .map(|step| (step.from, step.to))
+            .collect();
+        pawn_tries.dedup();
+        match pawn_tries.len() {
+            0 => {}
+            1 => announcements.push("1 pawn try".to_string()),
+            tries => announcements.push(format!("{} pawn tries", tries)),
+        }
+        announcements
+    }
+}
+
+// Long diagonal is the longer one of two diagonals going through the king
+fn check_direction(board: &Board, king: &Position, checker: &Position) -> &'static str {
+    let (hor_diff, vert_diff) = checker - king;
+    if hor_diff == 0 {
+        "Check on file"
+    } else if vert_diff == 0 {
+        "Check on rank"
+    } else if hor_diff.abs() != vert_diff.abs() {
+        "Knight check"
+    } else {
+        let (hor, vert) = (king.hor as i8, king.vert as i8);
+        let (width, height) = (board.width() as i8, board.height() as i8);
+        let rising_length = hor.min(vert) + (width - hor).min(height - vert);
+        let falling_length = hor.min(height - 1 - vert) + (width - hor).min(vert + 1);
+        let is_rising = (hor_diff > 0) == (vert_diff > 0);
+        if is_rising == (rising_length > falling_length) {
+            "Check on long diagonal"
+        } else {
+            "Check on short diagonal"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn announcements_after(fen: &str, step: &str) -> Vec<String> {
+        let variant = Rc::new(Kriegspiel::new());
+        let board = Board::from_variant_fen(fen, variant.clone()).unwrap();
+        let color = board.borrow().active_color();
+        let result = board
+            .borrow_mut()
+            .move_piece(&step.parse().unwrap(), color)
+            .unwrap();
+        let board = board.borrow();
+        variant.announcements(&board, &result)
+    }
+
+    #[test]
+    fn captures_and_checks() {
+        assert_eq!(
+            announcements_after("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "a1-a8"),
+            vec!["Check on rank"]
+        );
+        assert_eq!(
+            announcements_after("4k3/8/8/8/8/8/8/R2qK3 w - - 0 1", "a1-d1"),
+            vec!["Capture at D1"]
+        );
+        assert_eq!(
+            announcements_after("4k3/8/8/8/8/8/4B3/4K3 w - - 0 1", "e2-h5"),
+            vec!["Check on short diagonal"]
+        );
+        assert_eq!(
+            announcements_after("4k3/8/8/8/8/8/4B3/4K3 w - - 0 1", "e2-d3"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            announcements_after("8/8/8/8/8/8/8/1B2K2k w - - 0 1", "b1-e4"),
+            vec!["Check on long diagonal"]
+        );
+    }
+
+    #[test]
+    fn pawn_tries() {
+        assert_eq!(
+            announcements_after("4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1", "e2-e3"),
+            vec!["1 pawn try"]
+        );
+        assert_eq!(
+            announcements_after("4k3/8/8/8/8/3n1n2/4P3/K7 b - - 0 1", "e8-d8"),
+            vec!["2 pawn tries"]
+        );
+    }
+
+    #[test]
+    fn only_own_pieces_are_visible() {
+        let variant = Kriegspiel::new();
+        let board = Board::new_variant(Rc::new(Kriegspiel::new())).unwrap();
+        let board = board.borrow();
+        let visible = variant.visible_squares(&board, Color::Black).unwrap();
+        assert_eq!(
+            board.masked(&visible).to_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/8/8 w kq - 0 1"
+        );
+        let fen = "rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 1 1";
+        let board = Board::from_variant_fen(fen, Rc::new(Kriegspiel::new())).unwrap();
+        let board = board.borrow();
+        let visible = variant.visible_squares(&board, Color::Black).unwrap();
+        assert_eq!(
+            board.masked(&visible).to_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/8/8 b kq - 0 1"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use crate::board::Board;
+use crate::common::{Color, StepResultData};
+use crate::pieces::PieceType;
+use crate::positions::Vertical;
+use crate::variants::{self, Variant};
+
+pub struct LosAlamos;
+
+impl LosAlamos {
+    pub fn new() -> Self {
+        LosAlamos {}
+    }
+}
+
+impl Variant for LosAlamos {
+    fn name(&self) -> &'static str {
+        "losalamos"
+    }
+
+    fn description(&self) -> String {
+        "Los Alamos chess (6x6 board without bishops, castling and pawn double steps)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        "rnqknr/pppppp/6/6/PPPPPP/RNQKNR w - - 0 1".to_string()
+    }
+
+    fn dimensions(&self) -> (u8, u8) {
+        (6, 6)
+    }
+
+    // Castling rights given in a custom FEN don't make it possible either
+    fn is_legal(&self, before: &Board, after: &Board, result: &StepResultData) -> bool {
+        result.castling.is_none() && variants::is_legal_by_standard_rules(before, after, result)
+    }
+
+    fn double_step_ranks(&self, _color: Color) -> Vec<Vertical> {
+        Vec::new()
+    }
+
+    fn promotions(&self) -> Vec<PieceType> {
+        vec![PieceType::Queen, PieceType::Rook, PieceType::Knight]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn pawns_step_once_and_promote_on_sixth_rank() {
+        let board = Board::new_variant(Rc::new(LosAlamos::new())).unwrap();
+        assert!(board
+            .borrow_mut()
+            .move_piece(&"a2-a4".parse().unwrap(), Color::White)
+            .is_err());
+        let board = variants::board("losalamos", "3k2/P5/6/6/6/3K2 w - - 0 1");
+        assert!(board
+            .borrow_mut()
+            .move_piece(&"a5-a6b".parse().unwrap(), Color::White)
+            .is_err());
+        board
+            .borrow_mut()
+            .move_piece(&"a5-a6".parse().unwrap(), Color::White)
+            .unwrap();
+        assert_eq!(board.borrow().to_fen(), "Q2k2/6/6/6/6/3K2 b - - 0 1");
+    }
+
+    #[test]
+    fn castling_is_not_allowed() {
+        let fen = "r3k1/pppppp/6/6/PPPPPP/R3K1 w Qq - 0 1";
+        let board = variants::board("losalamos", fen);
+        assert!(board
+            .borrow_mut()
+            .move_piece(&"e1-c1".parse().unwrap(), Color::White)
+            .is_err());
+    }
+
+    #[test]
+    fn initial_perft() {
+        let board = Board::new_variant(Rc::new(LosAlamos::new())).unwrap();
+        assert_eq!(board.borrow().perft(1), 10);
+        assert_eq!(board.borrow().perft(2), 100);
+    }
+}
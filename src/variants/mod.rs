@@ -0,0 +1,214 @@
+use crate::board::Board;
+use crate::common::{
+    ChessError, ChessResult, Color, DrawReason, GameOutcome, StepResultData, WinReason,
+};
+use crate::moves::Move;
+use crate::pieces::PieceType;
+use crate::positions::{Position, Vertical};
+
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+pub mod almost_chess;
+pub mod antichess;
+pub mod atomic;
+pub mod bughouse;
+pub mod capablanca;
+pub mod chess960;
+pub mod crazyhouse;
+pub mod duck;
+pub mod fog_of_war;
+pub mod horde;
+pub mod king_of_the_hill;
+pub mod kriegspiel;
+pub mod los_alamos;
+pub mod racing_kings;
+pub mod standard;
+pub mod three_check;
+
+pub const NAMES: [&str; 16] = [
+    "standard",
+    "chess960",
+    "kingofthehill",
+    "threecheck",
+    "atomic",
+    "crazyhouse",
+    "antichess",
+    "racingkings",
+    "horde",
+    "kriegspiel",
+    "fogofwar",
+    "duck",
+    "almostchess",
+    "capablanca",
+    "losalamos",
+    "bughouse",
+];
+
+// Rules of the game, board consults it on every move
+pub trait Variant {
+    fn name(&self) -> &'static str;
+    fn description(&self) -> String {
+        self.name().to_string()
+    }
+    fn starting_fen(&self) -> String;
+    // Board width and height, up to positions::MAX_SIZE each
+    fn dimensions(&self) -> (u8, u8) {
+        (8, 8)
+    }
+    // Castling is done by moving king onto own rook
+    fn is_chess960(&self) -> bool {
+        false
+    }
+    // Captured pieces can be dropped back on the board
+    fn has_pockets(&self) -> bool {
+        false
+    }
+    // Two boards with four players, captured pieces go to the partner on the other board
+    fn is_team_game(&self) -> bool {
+        false
+    }
+    // Won by something else than mating or capturing the king, the engine's evaluation
+    // would lead the wrong way there
+    fn has_special_goal(&self) -> bool {
+        false
+    }
+    // Every move is followed by placing the duck, which blocks squares for both sides
+    fn has_duck(&self) -> bool {
+        false
+    }
+    // Final say on a single move, gets boards before and after it
+    fn is_legal(&self, before: &Board, after: &Board, result: &StepResultData) -> bool {
+        is_legal_by_standard_rules(before, after, result)
+    }
+    // Rules that need to see all legal moves at once, like compulsory captures
+    fn filter_moves(&self, _board: &Board, moves: Vec<Move>) -> Vec<Move> {
+        moves
+    }
+    // Same rules as in filter_moves for a single move, with explanation of what's wrong
+    fn check_move(&self, _board: &Board, _result: &StepResultData) -> ChessResult<()> {
+        Ok(())
+    }
+    // Ranks where pawns are allowed to make a double step from
+    fn double_step_ranks(&self, color: Color) -> Vec<Vertical> {
+        let (_, height) = self.dimensions();
+        vec![Board::relative_vertical(color, 1, height)]
+    }
+    fn promotions(&self) -> Vec<PieceType> {
+        vec![
+            PieceType::Queen,
+            PieceType::Rook,
+            PieceType::Bishop,
+            PieceType::Knight,
+        ]
+    }
+    // Side effects after the move is done and before the legality check,
+    // checkers in the result are already filled by standard rules
+    fn after_move(&self, _board: &mut Board, _result: &mut StepResultData) {}
+    // has_legal_moves tells if the side to move can move at all, so moves aren't generated again
+    fn outcome(&self, board: &Board, has_legal_moves: bool) -> Option<GameOutcome> {
+        standard_outcome(board, has_legal_moves)
+    }
+    // Squares with special meaning, highlighted on the board
+    fn marked_squares(&self) -> Vec<Position> {
+        Vec::new()
+    }
+    // Lines shown next to the board
+    fn notes(&self, _board: &Board) -> Vec<String> {
+        Vec::new()
+    }
+    // Extra FEN field after the move counters
+    fn fen_state(&self, _board: &Board) -> Option<String> {
+        None
+    }
+    // Squares the player can see, None if the whole board is visible.
+    // Games with hidden pieces are run by the server acting as a referee
+    fn visible_squares(&self, _board: &Board, _color: Color) -> Option<Vec<Position>> {
+        None
+    }
+    // Squares out of visible ones are drawn as fog instead of just being empty
+    fn shows_fog(&self) -> bool {
+        false
+    }
+    // Referee messages for both players after the move
+    fn announcements(&self, _board: &Board, _result: &StepResultData) -> Vec<String> {
+        Vec::new()
+    }
+    fn parse_fen_state(&self, _board: &mut Board, state: &str) -> ChessResult<()> {
+        Err(ChessError::InvalidInput(state.to_string()))
+    }
+}
+
+// Position is used only by variants with numbered starting positions
+pub fn create(name: &str, position: u32) -> ChessResult<Rc<dyn Variant>> {
+    match name {
+        "standard" => Ok(Rc::new(standard::Standard::new())),
+        "chess960" => {
+            let position = u16::try_from(position)
+                .map_err(|_| ChessError::InvalidInput(position.to_string()))?;
+            Ok(Rc::new(chess960::Chess960::new(position)?))
+        }
+        "kingofthehill" => Ok(Rc::new(king_of_the_hill::KingOfTheHill::new())),
+        "threecheck" => Ok(Rc::new(three_check::ThreeCheck::new())),
+        "atomic" => Ok(Rc::new(atomic::Atomic::new())),
+        "crazyhouse" => Ok(Rc::new(crazyhouse::Crazyhouse::new())),
+        "antichess" => Ok(Rc::new(antichess::Antichess::new())),
+        "racingkings" => Ok(Rc::new(racing_kings::RacingKings::new())),
+        "horde" => Ok(Rc::new(horde::Horde::new())),
+        "kriegspiel" => Ok(Rc::new(kriegspiel::Kriegspiel::new())),
+        "fogofwar" => Ok(Rc::new(fog_of_war::FogOfWar::new())),
+        "duck" => Ok(Rc::new(duck::Duck::new())),
+        "almostchess" => Ok(Rc::new(almost_chess::AlmostChess::new())),
+        "capablanca" => Ok(Rc::new(capablanca::Capablanca::new())),
+        "losalamos" => Ok(Rc::new(los_alamos::LosAlamos::new())),
+        "bughouse" => Ok(Rc::new(bughouse::Bughouse::new())),
+        _ => Err(ChessError::InvalidInput(name.to_string())),
+    }
+}
+
+// Board of the variant set up from FEN, shared by the variant tests
+#[cfg(test)]
+pub fn board(name: &str, fen: &str) -> Rc<std::cell::RefCell<Board>> {
+    Board::from_variant_fen(fen, create(name, 0).unwrap()).unwrap()
+}
+
+// King can't pass attacked squares while castling and can't stay in check
+pub fn is_legal_by_standard_rules(before: &Board, after: &Board, result: &StepResultData) -> bool {
+    let is_attacked = |square: &Position| !before.attackers_of(square, !result.color).is_empty();
+    !is_castling_path_attacked(before, result, is_attacked) && !after.is_in_check(result.color)
+}
+
+// Any square between the king's start and destination (both included) attacked by the opponent,
+// variants decide what counts as an attack
+pub fn is_castling_path_attacked(
+    before: &Board,
+    result: &StepResultData,
+    is_attacked: impl Fn(&Position) -> bool,
+) -> bool {
+    let side = match result.castling {
+        Some(side) => side,
+        None => return false,
+    };
+    let (king_to, _) = before.castling_destinations(result.color, side);
+    let king_from = result.step.from;
+    let (from, to) = if king_to.hor > king_from.hor {
+        (king_from.hor as i8, king_to.hor as i8)
+    } else {
+        (king_to.hor as i8, king_from.hor as i8)
+    };
+    (from..=to)
+        .filter_map(|hor| Position::at(hor, king_from.vert as i8))
+        .any(|square| is_attacked(&square))
+}
+
+// Checkmate or stalemate if side to move has nothing to do
+pub fn standard_outcome(board: &Board, has_legal_moves: bool) -> Option<GameOutcome> {
+    let color = board.active_color();
+    if has_legal_moves {
+        None
+    } else if board.is_in_check(color) {
+        Some(GameOutcome::Win(!color, WinReason::Checkmate))
+    } else {
+        Some(GameOutcome::Draw(DrawReason::Stalemate))
+    }
+}
@@ -0,0 +1,135 @@
+use crate::board::Board;
+use crate::common::{Color, DrawReason, GameOutcome, StepResultData, WinReason};
+use crate::pieces::PieceType;
+use crate::positions::Vertical;
+use crate::variants::{self, Variant};
+
+pub struct RacingKings;
+
+impl RacingKings {
+    pub fn new() -> Self {
+        RacingKings {}
+    }
+}
+
+impl Variant for RacingKings {
+    fn name(&self) -> &'static str {
+        "racingkings"
+    }
+
+    fn description(&self) -> String {
+        "Racing Kings (first king on the 8th rank wins, checks are not allowed)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1".to_string()
+    }
+
+    fn has_special_goal(&self) -> bool {
+        true
+    }
+
+    fn is_legal(&self, before: &Board, after: &Board, result: &StepResultData) -> bool {
+        variants::is_legal_by_standard_rules(before, after, result)
+            && !after.is_in_check(!result.color)
+    }
+
+    // Black gets one more move to equalize after white king reaches the goal
+    fn outcome(&self, board: &Board, has_legal_moves: bool) -> Option<GameOutcome> {
+        let white_reached = is_on_goal(board, Color::White);
+        let black_reached = is_on_goal(board, Color::Black);
+        match (white_reached, black_reached) {
+            (true, true) => Some(GameOutcome::Draw(DrawReason::BothKingsReachedGoal)),
+            (false, true) => Some(GameOutcome::Win(Color::Black, WinReason::KingReachedGoal)),
+            (true, false) if board.active_color() == Color::Black && can_reach_goal(board) => None,
+            (true, false) => Some(GameOutcome::Win(Color::White, WinReason::KingReachedGoal)),
+            (false, false) if !has_legal_moves => Some(GameOutcome::Draw(DrawReason::Stalemate)),
+            (false, false) => None,
+        }
+    }
+}
+
+fn is_on_goal(board: &Board, color: Color) -> bool {
+    board
+        .king_position(color)
+        .map(|king| king.vert == Vertical::Vert8)
+        .unwrap_or(false)
+}
+
+fn can_reach_goal(board: &Board) -> bool {
+    board.legal_moves(Color::Black).iter().any(|step| {
+        step.to.vert == Vertical::Vert8
+            && board
+                .cell(&step.from)
+                .as_ref()
+                .map(|piece| piece.piece_type == PieceType::King)
+                .unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn giving_check_is_illegal() {
+        let board = variants::board("racingkings", "8/8/8/8/8/k7/8/1R5K w - - 0 1");
+        assert!(board
+            .borrow_mut()
+            .move_piece(&"b1-b3".parse().unwrap(), Color::White)
+            .is_err());
+        assert!(board
+            .borrow_mut()
+            .move_piece(&"b1-b2".parse().unwrap(), Color::White)
+            .is_ok());
+    }
+
+    #[test]
+    fn black_can_equalize() {
+        let board = variants::board("racingkings", "8/1k4K1/8/8/8/8/8/8 w - - 0 1");
+        let result = board
+            .borrow_mut()
+            .move_piece(&"g7-g8".parse().unwrap(), Color::White)
+            .unwrap();
+        assert_eq!(result.outcome, None);
+        let result = board
+            .borrow_mut()
+            .move_piece(&"b7-c6".parse().unwrap(), Color::Black)
+            .unwrap();
+        assert_eq!(
+            result.outcome,
+            Some(GameOutcome::Win(Color::White, WinReason::KingReachedGoal))
+        );
+
+        let board = variants::board("racingkings", "8/6K1/8/1k6/8/8/8/8 w - - 0 1");
+        let result = board
+            .borrow_mut()
+            .move_piece(&"g7-g8".parse().unwrap(), Color::White)
+            .unwrap();
+        assert_eq!(
+            result.outcome,
+            Some(GameOutcome::Win(Color::White, WinReason::KingReachedGoal))
+        );
+
+        let board = variants::board("racingkings", "8/1k4K1/8/8/8/8/8/8 w - - 0 1");
+        board
+            .borrow_mut()
+            .move_piece(&"g7-g8".parse().unwrap(), Color::White)
+            .unwrap();
+        let result = board
+            .borrow_mut()
+            .move_piece(&"b7-b8".parse().unwrap(), Color::Black)
+            .unwrap();
+        assert_eq!(
+            result.outcome,
+            Some(GameOutcome::Draw(DrawReason::BothKingsReachedGoal))
+        );
+    }
+
+    #[test]
+    fn initial_perft() {
+        let board = Board::new_variant(Rc::new(RacingKings::new())).unwrap();
+        assert_eq!(board.borrow().perft(3), 11264);
+    }
+}
@@ -0,0 +1,19 @@
+use crate::variants::Variant;
+
+pub struct Standard;
+
+impl Standard {
+    pub fn new() -> Self {
+        Standard {}
+    }
+}
+
+impl Variant for Standard {
+    fn name(&self) -> &'static str {
+        "standard"
+    }
+
+    fn starting_fen(&self) -> String {
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string()
+    }
+}
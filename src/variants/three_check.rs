@@ -0,0 +1,121 @@
+use crate::board::Board;
+use crate::common::{ChessError, ChessResult, Color, GameOutcome, StepResultData, WinReason};
+use crate::variants::standard::Standard;
+use crate::variants::{self, Variant};
+
+const CHECKS_TO_WIN: u32 = 3;
+
+pub struct ThreeCheck;
+
+impl ThreeCheck {
+    pub fn new() -> Self {
+        ThreeCheck {}
+    }
+}
+
+impl Variant for ThreeCheck {
+    fn name(&self) -> &'static str {
+        "threecheck"
+    }
+
+    fn description(&self) -> String {
+        "Three-check (third check given wins)".to_string()
+    }
+
+    fn starting_fen(&self) -> String {
+        format!("{} +0+0", Standard::new().starting_fen())
+    }
+
+    fn has_special_goal(&self) -> bool {
+        true
+    }
+
+    fn after_move(&self, board: &mut Board, result: &mut StepResultData) {
+        // Checkers are already filled by the standard rules
+        if result.is_check() {
+            let checks = board.checks_given(result.color);
+            board.set_checks_given(result.color, checks + 1);
+        }
+    }
+
+    fn outcome(&self, board: &Board, has_legal_moves: bool) -> Option<GameOutcome> {
+        let color = !board.active_color();
+        if board.checks_given(color) >= CHECKS_TO_WIN {
+            Some(GameOutcome::Win(color, WinReason::ThreeChecks))
+        } else {
+            variants::standard_outcome(board, has_legal_moves)
+        }
+    }
+
+    fn notes(&self, board: &Board) -> Vec<String> {
+        vec![
+            "Checks given:".to_string(),
+            format!("White: {}", board.checks_given(Color::White)),
+            format!("Black: {}", board.checks_given(Color::Black)),
+        ]
+    }
+
+    fn fen_state(&self, board: &Board) -> Option<String> {
+        Some(format!(
+            "+{}+{}",
+            board.checks_given(Color::White),
+            board.checks_given(Color::Black)
+        ))
+    }
+
+    // Checks given by white and black as "+1+0"
+    fn parse_fen_state(&self, board: &mut Board, state: &str) -> ChessResult<()> {
+        let invalid = || ChessError::InvalidInput(state.to_string());
+        let counters: Vec<u32> = state
+            .split('+')
+            .skip(1)
+            .map(|checks| checks.parse().map_err(|_| invalid()))
+            .collect::<ChessResult<_>>()?;
+        match (state.starts_with('+'), counters.as_slice()) {
+            (true, [white, black]) if *white <= CHECKS_TO_WIN && *black <= CHECKS_TO_WIN => {
+                board.set_checks_given(Color::White, *white);
+                board.set_checks_given(Color::Black, *black);
+                Ok(())
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn checks_are_counted_in_fen() {
+        let board = variants::board("threecheck", "4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+        assert_eq!(
+            board.borrow().to_fen(),
+            "4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +0+0"
+        );
+        let step = "a1-a8".parse().unwrap();
+        let result = board.borrow_mut().move_piece(&step, Color::White).unwrap();
+        assert_eq!(result.outcome, None);
+        assert_eq!(
+            board.borrow().to_fen(),
+            "R3k3/8/8/8/8/8/8/4K3 b - - 1 1 +1+0"
+        );
+        assert!(Board::from_variant_fen(
+            "8/8/8/8/8/8/8/8 w - - 0 1 1+0",
+            Rc::new(ThreeCheck::new())
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn third_check_wins() {
+        let board = variants::board("threecheck", "4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0");
+        let step = "a1-a8".parse().unwrap();
+        let result = board.borrow_mut().move_piece(&step, Color::White).unwrap();
+        assert_eq!(
+            result.outcome,
+            Some(GameOutcome::Win(Color::White, WinReason::ThreeChecks))
+        );
+    }
+}